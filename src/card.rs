@@ -0,0 +1,273 @@
+//! Playing cards.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Suit of a natural card.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Suit{
+    /// ♠, s
+    Spades,
+    /// ♡, h
+    Hearts,
+    /// ♢, d
+    Diamonds,
+    /// ♣, c
+    Clubs,
+}
+impl Suit{
+    /// Every suit, in the order of the Unicode suit symbols.
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// The Unicode symbol of the suit, as drawn on the screen.
+    pub fn symbol(&self)->char{
+        match self{
+            Suit::Spades => '\u{2660}',
+            Suit::Hearts => '\u{2661}',
+            Suit::Diamonds => '\u{2662}',
+            Suit::Clubs => '\u{2663}',
+        }
+    }
+
+    /// The letter of the suit, as written in card names like "Ah".
+    pub fn letter(&self)->char{
+        match self{
+            Suit::Spades => 's',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+        }
+    }
+}
+impl fmt::Display for Suit{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        write!(f, "{}", self.letter())
+    }
+}
+impl FromStr for Suit{
+    type Err = String;
+
+    /// Parses a suit letter (s, h, d, c) or symbol.
+    fn from_str(s: &str)->Result<Self, Self::Err>{
+        Suit::ALL.into_iter()
+        .find(|suit|{
+            s.eq_ignore_ascii_case(&suit.letter().to_string())
+            || s == suit.symbol().to_string()
+        })
+        .ok_or(format!("Invalid suit {}", s))
+    }
+}
+
+/// Rank of a natural card, aces low.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Rank{
+    /// The lowest rank, also the highest in an ace high straight
+    Ace = 1,
+    /// 2
+    Two,
+    /// 3
+    Three,
+    /// 4
+    Four,
+    /// 5
+    Five,
+    /// 6
+    Six,
+    /// 7
+    Seven,
+    /// 8
+    Eight,
+    /// 9
+    Nine,
+    /// 10
+    Ten,
+    /// J
+    Jack,
+    /// Q
+    Queen,
+    /// K
+    King,
+}
+impl Rank{
+    /// Every rank, from the ace to the king.
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    /// Numeric value of the rank, from 1 (ace) to 13 (king).
+    pub fn value(&self)->u8{
+        *self as u8
+    }
+
+    /// Returns None, if the value is not between 1 and 13.
+    pub fn from_value(value: u8)->Option<Rank>{
+        Rank::ALL.get(usize::from(value).checked_sub(1)?).copied()
+    }
+
+    fn as_str(&self)->&'static str{
+        match self{
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+impl fmt::Display for Rank{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        f.pad(self.as_str())
+    }
+}
+impl FromStr for Rank{
+    type Err = String;
+
+    /// Parses A, 2-10, J, Q or K, T is also accepted for ten.
+    fn from_str(s: &str)->Result<Self, Self::Err>{
+        if s.eq_ignore_ascii_case("T"){
+            return Ok(Rank::Ten);
+        }
+        Rank::ALL.into_iter()
+        .find(|rank| s.eq_ignore_ascii_case(rank.as_str()))
+        .ok_or(format!("Invalid rank {}", s))
+    }
+}
+
+/// A playing card, written like "Ah", "10s" or "JK" for the joker.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Card{
+    /// Any card but the joker
+    Natural{
+        /// Rank of the card
+        rank: Rank,
+        /// Suit of the card
+        suit: Suit,
+    },
+    /// Wild card, counts as whatever makes the best hand
+    Joker,
+}
+impl Card{
+    /// A natural card of the rank and suit.
+    pub fn new(rank: Rank, suit: Suit)->Card{
+        Card::Natural{
+            rank,
+            suit,
+        }
+    }
+
+    /// Returns true, if the card is a joker (wild card).
+    pub fn is_joker(&self)->bool{
+        *self == Card::Joker
+    }
+
+    /// Rank of the card, None for the joker.
+    pub fn rank(&self)->Option<Rank>{
+        match self{
+            Card::Natural{ rank, .. } => Some(*rank),
+            Card::Joker => None,
+        }
+    }
+
+    /// Suit of the card, None for the joker.
+    pub fn suit(&self)->Option<Suit>{
+        match self{
+            Card::Natural{ suit, .. } => Some(*suit),
+            Card::Joker => None,
+        }
+    }
+
+    /// Every natural card, by rank and then by suit.
+    pub fn natural_cards()->impl Iterator<Item = Card>{
+        Rank::ALL.into_iter()
+        .flat_map(|rank| Suit::ALL.into_iter().map(move |suit| Card::new(rank, suit)))
+    }
+    // pub fn print(&self){
+    //     println!("Value: {} Suit: {}", self.value, self.suit);
+    // }
+}
+impl fmt::Display for Card{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        match self{
+            Card::Natural{ rank, suit } => f.pad(&format!("{}{}", rank, suit)),
+            Card::Joker => f.pad("JK"),
+        }
+    }
+}
+impl FromStr for Card{
+    type Err = String;
+
+    fn from_str(s: &str)->Result<Self, Self::Err>{
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("JK"){
+            return Ok(Card::Joker);
+        }
+        // the suit is the last character, the rank everything before it
+        let split = s.char_indices().last().map_or(0, |(i, _)| i);
+        let (rank, suit) = s.split_at(split);
+        match (rank.parse(), suit.parse()){
+            (Ok(rank), Ok(suit)) => Ok(Card::new(rank, suit)),
+            _ => Err(format!("Invalid card {}", s)),
+        }
+    }
+}
+impl From<Card> for String{
+    fn from(card: Card)->String{
+        card.to_string()
+    }
+}
+impl TryFrom<String> for Card{
+    type Error = String;
+
+    fn try_from(s: String)->Result<Self, Self::Error>{
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parse_cards(){
+        assert_eq!("Ah".parse(), Ok(Card::new(Rank::Ace, Suit::Hearts)));
+        assert_eq!("10s".parse(), Ok(Card::new(Rank::Ten, Suit::Spades)));
+        assert_eq!("qD".parse(), Ok(Card::new(Rank::Queen, Suit::Diamonds)));
+        assert_eq!("JK".parse(), Ok(Card::Joker));
+        assert_eq!("K\u{2663}".parse(), Ok(Card::new(Rank::King, Suit::Clubs)));
+        assert!("1s".parse::<Card>().is_err());
+        assert!("Ax".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+
+        // every card reads back from its name
+        for card in Card::natural_cards().chain([Card::Joker]){
+            assert_eq!(card.to_string().parse(), Ok(card));
+        }
+        assert_eq!(Card::natural_cards().count(), 52);
+        assert_eq!(Rank::from_value(11), Some(Rank::Jack));
+        assert_eq!(Rank::from_value(0), None);
+        assert_eq!(Rank::from_value(14), None);
+    }
+}
@@ -0,0 +1,56 @@
+//! Settings file of the terminal game, the command line overrides it.
+
+use rust_jokeri_pokeri::Rules;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Preferences of the terminal front-end.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig{
+    /// Line based terminal instead of the full-screen one
+    pub plain: bool,
+    /// Hands played at once in a new game
+    pub hands: usize,
+    /// Value of a credit in a new game, in euros like "0.20"
+    pub denomination: String,
+    /// Money put in with the cash in key, in euros
+    pub cash_in: String,
+}
+impl Default for UiConfig{
+    fn default()->Self{
+        Self{
+            plain: false,
+            hands: 1,
+            denomination: "0.20".to_string(),
+            cash_in: "10.00".to_string(),
+        }
+    }
+}
+
+/// The settings file, every value is optional.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config{
+    /// Paytable file relative to the settings file, the built-in one if not set
+    pub paytable: Option<PathBuf>,
+    pub rules: Rules,
+    pub ui: UiConfig,
+}
+impl Config{
+    /// Reads a TOML settings file. A missing file gives the defaults,
+    /// unless it must exist.
+    pub fn load(path: &Path, must_exist: bool)->Result<Self, String>{
+        if !must_exist && !path.exists(){
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can not read settings {}: {}", path.display(), e))?;
+        let mut config: Config = toml::from_str(&text)
+        .map_err(|e| format!("Invalid settings {}: {}", path.display(), e))?;
+        if let (Some(paytable), Some(dir)) = (&config.paytable, path.parent()){
+            config.paytable = Some(dir.join(paytable));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,95 @@
+//! A deck of cards with its own random number generator.
+
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::card::Card;
+
+/// Jokers in the deck of the original machine.
+pub const DEFAULT_JOKERS: usize = 2;
+
+/// Deck of cards, shuffled with its own random number generator.
+/// The same seed always gives the same deals.
+#[derive(Serialize, Deserialize)]
+pub struct Deck<R: RngCore = ChaCha8Rng> {
+    /// Cards left in the deck, the next one to deal first
+    pub cards: Vec<Card>,
+    rng: R,
+}
+impl Deck{
+    /// Creates a filled and shuffled Deck from a seed.
+    pub fn new(seed: u64)->Self{
+        Self::with_jokers(seed, DEFAULT_JOKERS)
+    }
+
+    /// Creates a shuffled Deck with the number of jokers from a seed.
+    pub fn with_jokers(seed: u64, jokers: usize)->Self{
+        Self::with_rng(ChaCha8Rng::seed_from_u64(seed), jokers)
+    }
+}
+impl<R: RngCore> Deck<R>{
+    /// Creates a filled Deck shuffled with the given generator.
+    pub fn with_rng(rng: R, jokers: usize)->Self{
+        let mut deck = Self { 
+            cards: Vec::new(),
+            rng,
+        };
+        deck.fill_deck(jokers);
+        //deck.print();
+        deck.shuffle_deck();
+        deck
+    }
+
+    // fn print(&mut self){
+    //     for i in &self.cards{
+    //         println!("{} {}", i.value, i.suit);
+    //     }
+    // }
+    /// Takes the next card, None when the deck is empty.
+    pub fn deal(&mut self)->Option<Card>{
+        if self.cards.is_empty(){
+            return None;
+        }
+        Some(self.cards.remove(0))
+    }
+    /// Shuffles the cards left in the deck.
+    pub fn shuffle_deck(&mut self){
+        self.cards.shuffle(&mut self.rng);
+    }
+    /// Adds the 52 natural cards and the jokers.
+    pub fn fill_deck(&mut self, jokers: usize){
+        // add base cards
+        self.cards.extend(Card::natural_cards());
+        // add joker cards
+        self.cards.extend(std::iter::repeat_n(Card::Joker, jokers));
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn fill_deck(){
+        let deck = Deck::new(0);
+        assert_eq!(deck.cards.len(), 54);
+        assert_eq!(deck.cards.iter().filter(|card| card.is_joker()).count(), 2);
+
+        let deck = Deck::with_jokers(0, 0);
+        assert_eq!(deck.cards.len(), 52);
+        // the same seed deals the same with the default jokers
+        assert_eq!(Deck::with_jokers(0, DEFAULT_JOKERS).cards, Deck::new(0).cards);
+    }
+    #[test]
+    fn deal_until_empty(){
+        let mut deck = Deck::new(0);
+        let first = deck.cards[0];
+        assert_eq!(deck.deal(), Some(first));
+        while deck.deal().is_some(){}
+        assert!(deck.cards.is_empty());
+        assert_eq!(deck.deal(), None);
+    }
+}
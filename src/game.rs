@@ -0,0 +1,1347 @@
+//! The game's state machine, without any input or output.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use crate::card::{Card, Rank};
+use crate::deck::Deck;
+use crate::hand::{Hand, Prize};
+use crate::history::HandRecord;
+use crate::jackpot::Jackpot;
+use crate::money::Denomination;
+use crate::persist::save_json;
+use crate::rules::Rules;
+use crate::paytable::Paytable;
+
+/// States of a round, in the order they are played.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GameState{
+    /// Between rounds, choosing the bet
+    Betting,
+    /// Choosing the cards to hold before the draw
+    HandSelection,
+    /// Guessing the next card to double the win
+    DoubleUp,
+    /// After the draw, the win on the win meter
+    PayOut,
+    /// The game ended, out of funds or cashed out
+    GameOver,
+}
+
+/// The player's guess for the next card in the double up round.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DoubleUpGuess{
+    /// Ace to six
+    Small,
+    /// Eight to king
+    Large,
+}
+impl DoubleUpGuess{
+    /// Returns true, if the guess wins against the card.
+    /// Sevens always lose and jokers always win.
+    pub fn wins(&self, card: &Card)->bool{
+        match (self, card.rank()){
+            (_, None) => true,
+            (DoubleUpGuess::Small, Some(rank)) => rank <= Rank::Six,
+            (DoubleUpGuess::Large, Some(rank)) => rank >= Rank::Eight,
+        }
+    }
+}
+
+/// The biggest win that can still be doubled.
+pub const DOUBLE_UP_LIMIT: u32 = 5000;
+
+/// Most hands that can be played at once.
+pub const MAX_HANDS: usize = 100;
+
+/// What happened in the game, returned by every action of the engine
+/// so that front-ends can react without inspecting the whole state.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameEvent{
+    /// The bet for the next round changed
+    BetChanged{
+        /// Bet of each hand
+        bet: u32,
+    },
+    /// The number of hands for the next round changed
+    HandsChanged{
+        /// Hands played at once
+        hands: usize,
+    },
+    /// The value of a credit changed
+    DenominationChanged{
+        /// The new value
+        denomination: Denomination,
+    },
+    /// The bet was taken from funds
+    RoundStarted{
+        /// Number of the round
+        round: u32,
+        /// Bet of each hand
+        bet: u32,
+        /// Funds left after the bet
+        funds: u32,
+    },
+    /// The five cards dealt at the start of a round
+    Dealt{
+        /// The dealt cards in slot order
+        cards: [Card; 5],
+    },
+    /// A card was held or released
+    HoldToggled{
+        /// Slot of the card
+        slot: usize,
+        /// True, if the card is now held
+        held: bool,
+    },
+    /// The draw of one hand of the round, with the prize and payout
+    Drawn(HandRecord),
+    /// The double up round started
+    DoubleUpStarted{
+        /// The win risked on the guess
+        stake: u32,
+    },
+    /// The double up round was left without guessing
+    DoubleUpCancelled,
+    /// The double up card was dealt, the win doubled or lost
+    DoubleUpResolved{
+        /// The dealt card
+        card: Card,
+        /// True, if the guess was right
+        won: bool,
+        /// The win after the guess
+        pending_win: u32,
+    },
+    /// Win moved from the win meter to funds
+    Collected{
+        /// Credits moved
+        amount: u32,
+        /// Funds after collecting
+        funds: u32,
+    },
+    /// The jackpot pool was paid straight to funds
+    JackpotWon{
+        /// Credits paid from the pool
+        amount: u32,
+        /// Funds after the jackpot
+        funds: u32,
+    },
+    /// The round was over and the next one can start
+    RoundEnded{
+        /// Number of the finished round
+        round: u32,
+    },
+    /// The funds do not cover the smallest bet, or were cashed out
+    GameOver{
+        /// The round the game ended on
+        round: u32,
+    },
+    /// A new game started
+    NewGame,
+    /// Money was put in the machine as credits
+    CashedIn{
+        /// Credits bought
+        credits: u32,
+        /// Funds after cashing in
+        funds: u32,
+    },
+    /// Every credit was paid out, worth the cents
+    CashedOut{
+        /// Credits paid out
+        credits: u32,
+        /// Their value in cents
+        cents: u64,
+    },
+}
+
+/// Why an action of the engine was refused. The game is left as it
+/// was before the action.
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameError{
+    /// The wager of every hand is more than the funds
+    InsufficientFunds{
+        /// The bet of every hand together
+        wager: u32,
+        /// The funds on the meter
+        funds: u32,
+    },
+    /// No cards left to deal
+    DeckExhausted,
+    /// The action can not be taken in the state
+    IllegalAction{
+        /// What was tried, like "draw"
+        action: &'static str,
+        /// The state the game was in
+        state: GameState,
+    },
+    /// Bet not in the allowed bets of the rules
+    InvalidBet(u32),
+    /// Hand count of zero or above MAX_HANDS
+    InvalidHands(usize),
+    /// No such slot in the hand
+    InvalidSlot(usize),
+    /// A sum of credits does not fit in the counters
+    Overflow,
+}
+impl fmt::Display for GameError{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        match self{
+            GameError::InsufficientFunds{ wager, funds } =>{
+                write!(f, "Not enough funds, the bet needs {} and there is {}", wager, funds)
+            }
+            GameError::DeckExhausted => write!(f, "No cards left in the deck"),
+            GameError::IllegalAction{ action, state } =>{
+                write!(f, "Can not {} in state {:?}", action, state)
+            }
+            GameError::InvalidBet(bet) => write!(f, "Bet {} is not one of the allowed bets", bet),
+            GameError::InvalidHands(hands) =>{
+                write!(f, "Invalid number of hands {}, use 1-{}", hands, MAX_HANDS)
+            }
+            GameError::InvalidSlot(slot) => write!(f, "No card in slot {}", slot),
+            GameError::Overflow => write!(f, "Too many credits to count"),
+        }
+    }
+}
+impl std::error::Error for GameError{}
+
+/// Result of an action of the engine.
+pub type GameResult = Result<Vec<GameEvent>, GameError>;
+
+/// Version of the save file format, bumped on incompatible changes.
+pub const SAVE_VERSION: u32 = 2;
+
+/// Struct to hold all of the game's data and rules, without any input
+/// or output. Every action checks the current state and returns what
+/// happened, or the error of an action that changed nothing.
+#[derive(Serialize, Deserialize)]
+pub struct JokeriPokeri{
+    /// Cards left to deal
+    deck: Deck,
+    /// Seed of the deck, shown so that a game can be replayed.
+    seed: u64,
+    hand: Hand,
+    /// Cards dealt this round, put back to the deck when it ends
+    discarded: Vec<Card>,
+    /// The credit meter
+    funds: u32,
+    /// Value of a credit
+    #[serde(default)]
+    denomination: Denomination,
+    /// Money put in the machine in cents, the starting credits included
+    #[serde(default)]
+    cashed_in: u64,
+    /// Money paid out of the machine in cents
+    #[serde(default)]
+    cashed_out: u64,
+    /// Number of the round, starting from 1
+    round: u32,
+    bet_amount: u32,
+    /// Prize of the latest draw
+    latest_prize: Option<Prize>,
+    /// What the latest draw paid, before any doubling
+    latest_payout: u32,
+    /// Win not yet collected to funds, may still be gambled.
+    pending_win: u32,
+    /// Latest card dealt in the double up round
+    double_up_card: Option<Card>,
+    paytable: Paytable,
+    state: GameState,
+    playing: bool,
+    /// Slots held for the draw
+    selected: [bool; 5],
+    /// Shared by every game on the machine, so saved apart from the game
+    #[serde(skip)]
+    jackpot: Jackpot,
+    /// Hands played besides the dealt one, getting copies of its held cards
+    #[serde(default)]
+    extra_hands: Vec<Hand>,
+    /// Own deck of each extra hand, refilled from the deck on every draw
+    #[serde(default)]
+    extra_decks: Vec<Deck>,
+    /// Most credits on the meter this game, not counting the bought ones
+    #[serde(default)]
+    peak_funds: u32,
+    /// Credits cashed in during this game
+    #[serde(default)]
+    bought_credits: u32,
+    /// Biggest payout of a draw this game, the jackpot included
+    #[serde(default)]
+    biggest_payout: u32,
+    /// Saved with the game, a resumed game keeps the rules it started with
+    #[serde(default)]
+    rules: Rules,
+}
+impl JokeriPokeri{
+    /// Starts a game with the paytable, the deck shuffled from the seed.
+    pub fn new(paytable: Paytable, seed: u64)->Self{
+        Self::with_rules(paytable, Rules::default(), seed)
+    }
+
+    /// Starts a game with the rules, checked with Rules::validate.
+    pub fn with_rules(paytable: Paytable, rules: Rules, seed: u64)->Self{
+        let game: JokeriPokeri = JokeriPokeri { 
+            deck: Deck::with_jokers(seed, rules.jokers), 
+            seed,
+            hand: Hand::new(),
+            discarded: Vec::new(),
+            funds: rules.starting_funds,
+            denomination: Denomination::default(),
+            cashed_in: Denomination::default().value(rules.starting_funds),
+            cashed_out: 0,
+            round: 1, 
+            bet_amount: rules.min_bet(),
+            latest_payout: 0,
+            pending_win: 0,
+            double_up_card: None,
+            paytable,
+            state: GameState::Betting,
+            playing: true, 
+            selected: [false, false, false, false, false],
+            latest_prize: None,
+            jackpot: Jackpot::default(),
+            extra_hands: Vec::new(),
+            extra_decks: Vec::new(),
+            peak_funds: rules.starting_funds,
+            bought_credits: 0,
+            biggest_payout: 0,
+            rules,
+        };
+        game
+    }
+
+    /// Starts over after game over with the starting funds, on top of
+    /// any credits left, keeping the deck.
+    pub fn reset_game(&mut self)->GameResult{
+        self.require(GameState::GameOver, "start a new game")?;
+        let funds = self.funds.checked_add(self.rules.starting_funds).ok_or(GameError::Overflow)?;
+        self.cashed_in += self.denomination.value(self.rules.starting_funds);
+        self.start_game(funds);
+        Ok(vec![GameEvent::NewGame])
+    }
+
+    /// Starts a new scored game with the funds.
+    fn start_game(&mut self, funds: u32){
+        // keep the deck and its generator, so a seed replays whole sessions
+        self.reset_deck_and_hand();
+        self.funds = funds;
+        self.peak_funds = funds;
+        self.bought_credits = 0;
+        self.biggest_payout = 0;
+        self.round = 1;
+        self.bet_amount = self.rules.min_bet();
+        self.latest_payout = 0;
+        self.pending_win = 0;
+        self.double_up_card = None;
+        self.state = GameState::Betting;
+        self.playing = true;
+        self.selected = [false, false, false, false, false];
+        self.latest_prize = None;
+    }
+
+    /// Writes the whole game state to a file.
+    pub fn save(&self, path: &Path)->Result<(), String>{
+        let save = serde_json::json!({
+            "version": SAVE_VERSION,
+            "game": self,
+        });
+        save_json(&save, path, "game")
+    }
+
+    /// Reads a game saved with save.
+    pub fn load(path: &Path)->Result<Self, String>{
+        let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can not read saved game {}: {}", path.display(), e))?;
+        let mut save: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| format!("Invalid saved game {}: {}", path.display(), e))?;
+        match save["version"].as_u64(){
+            Some(version) if version == u64::from(SAVE_VERSION)=>{}
+            Some(version)=>{
+                return Err(format!("Saved game {} has unsupported version {}",
+                path.display(), version));
+            }
+            None=>{
+                return Err(format!("Saved game {} has no version", path.display()));
+            }
+        }
+        serde_json::from_value(save["game"].take())
+        .map_err(|e| format!("Invalid saved game {}: {}", path.display(), e))
+    }
+
+    /// Builds the history record of a drawn hand of the finished round
+    /// from the hand dealt at the start of it.
+    fn history_record(&self, initial: &[Option<Card>; 5], hand: &Hand)->Result<HandRecord, GameError>{
+        let mut replacements = Vec::new();
+        for i in 0..hand.cards.len(){
+            if !self.selected[i]{
+                replacements.push(hand.cards[i].unwrap());
+            }
+        }
+        let prize = hand.evaluate();
+        Ok(HandRecord{
+            round: self.round,
+            hand: 0,
+            bet: self.bet_amount,
+            initial: initial.map(|card| card.unwrap()),
+            held: self.selected,
+            replacements,
+            prize,
+            payout: prize.map_or(Ok(0), |prize| self.paytable.payout(prize, self.bet_amount))?,
+            jackpot: 0,
+        })
+    }
+
+    /// Deals cards from deck to every unselected slot in hand.
+    /// Discards all unselected cards if in hand.
+    fn deal(&mut self)->Result<(), GameError>{
+        let needed = self.selected.iter().filter(|&&held| !held).count();
+        if self.deck.cards.len() < needed{
+            return Err(GameError::DeckExhausted);
+        }
+        for i in 0..self.hand.cards.len(){
+            // skip those cards that are selected to hold
+            if !self.selected[i]{
+                // discard if unselected card in hand
+                if let Some(card) = self.hand.cards[i].take(){
+                    self.discarded.push(card);
+                }
+                // draw a new card to hand.
+                self.hand.cards[i] = self.deck.deal();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an IllegalAction error, if not in the state.
+    fn require(&self, state: GameState, action: &'static str)->Result<(), GameError>{
+        if self.state != state{
+            return Err(GameError::IllegalAction{ action, state: self.state });
+        }
+        Ok(())
+    }
+
+    fn reset_deck_and_hand(&mut self){
+        // discard hand to discard pile
+        for i in 0.. self.hand.cards.len(){
+            if let Some(card) = self.hand.cards[i].take(){
+                self.discarded.push(card);
+            }
+        }
+        if let Some(card) = self.double_up_card.take(){
+            self.discarded.push(card);
+        }
+        // the extra hands hold copies, their cards are not from the deck
+        for hand in &mut self.extra_hands{
+            hand.cards = [None; 5];
+        }
+
+        // discard discard back to deck
+        for _i in 0..self.discarded.len(){
+            self.deck.cards.push(self.discarded.remove(0));
+        }
+        // shuffle deck
+        self.deck.shuffle_deck();
+    }
+
+    /// Number of hands played each round.
+    pub fn hands(&self)->usize{
+        self.extra_hands.len() + 1
+    }
+
+    /// The state of the round.
+    pub fn state(&self)->GameState{
+        self.state
+    }
+
+    /// The dealt hand.
+    pub fn hand(&self)->&Hand{
+        &self.hand
+    }
+
+    /// Hands played besides the dealt one.
+    pub fn extra_hands(&self)->&[Hand]{
+        &self.extra_hands
+    }
+
+    /// Slots held for the draw.
+    pub fn selected(&self)->[bool; 5]{
+        self.selected
+    }
+
+    /// Cards left to deal.
+    pub fn deck(&self)->&Deck{
+        &self.deck
+    }
+
+    /// Seed of the deck, to replay the game with.
+    pub fn seed(&self)->u64{
+        self.seed
+    }
+
+    /// The credit meter.
+    pub fn funds(&self)->u32{
+        self.funds
+    }
+
+    /// Number of the round, starting from 1.
+    pub fn round(&self)->u32{
+        self.round
+    }
+
+    /// Bet of each hand, of the round being played or the next one.
+    pub fn bet_amount(&self)->u32{
+        self.bet_amount
+    }
+
+    /// Prize of the latest draw of the dealt hand.
+    pub fn latest_prize(&self)->Option<Prize>{
+        self.latest_prize
+    }
+
+    /// What the latest draw paid, before any doubling.
+    pub fn latest_payout(&self)->u32{
+        self.latest_payout
+    }
+
+    /// Win on the win meter, not yet collected to funds.
+    pub fn pending_win(&self)->u32{
+        self.pending_win
+    }
+
+    /// Latest card dealt in the double up round.
+    pub fn double_up_card(&self)->Option<Card>{
+        self.double_up_card
+    }
+
+    /// The paytable the prizes are paid by.
+    pub fn paytable(&self)->&Paytable{
+        &self.paytable
+    }
+
+    /// The rules the game started with.
+    pub fn rules(&self)->&Rules{
+        &self.rules
+    }
+
+    /// The progressive jackpot pool.
+    pub fn jackpot(&self)->&Jackpot{
+        &self.jackpot
+    }
+
+    /// Plays on with the jackpot pool shared by the machine.
+    pub fn set_jackpot(&mut self, jackpot: Jackpot){
+        self.jackpot = jackpot;
+    }
+
+    /// Value of a credit.
+    pub fn denomination(&self)->Denomination{
+        self.denomination
+    }
+
+    /// Money put in the machine in cents, the starting credits included.
+    pub fn cashed_in(&self)->u64{
+        self.cashed_in
+    }
+
+    /// Money paid out of the machine in cents.
+    pub fn cashed_out(&self)->u64{
+        self.cashed_out
+    }
+
+    /// Most credits on the meter this game, not counting the bought ones.
+    pub fn peak_funds(&self)->u32{
+        self.peak_funds
+    }
+
+    /// Biggest payout of a draw this game, the jackpot included.
+    pub fn biggest_payout(&self)->u32{
+        self.biggest_payout
+    }
+
+    /// Everything bet on a round, the bet of every hand.
+    pub fn wager(&self, bet: u32)->Result<u32, GameError>{
+        u32::try_from(self.hands()).ok().and_then(|hands| bet.checked_mul(hands))
+        .ok_or(GameError::Overflow)
+    }
+
+    /// Sets how many hands the held cards are played on.
+    pub fn set_hands(&mut self, hands: usize)->GameResult{
+        self.require(GameState::Betting, "change the hands")?;
+        if hands == 0 || hands > MAX_HANDS{
+            return Err(GameError::InvalidHands(hands));
+        }
+        self.extra_hands.resize_with(hands - 1, Hand::new);
+        while self.extra_decks.len() < hands - 1{
+            let seed = self.seed.wrapping_add(self.extra_decks.len() as u64 + 1);
+            self.extra_decks.push(Deck::new(seed));
+        }
+        self.extra_decks.truncate(hands - 1);
+        Ok(vec![GameEvent::HandsChanged{ hands }])
+    }
+
+    /// Takes the bet of every hand from funds and deals a new hand.
+    pub fn start_round(&mut self, bet: u32)->GameResult{
+        self.require(GameState::Betting, "start a round")?;
+        if !self.rules.bets.contains(&bet){
+            return Err(GameError::InvalidBet(bet));
+        }
+        let wager = self.wager(bet)?;
+        let funds = self.funds.checked_sub(wager)
+        .ok_or(GameError::InsufficientFunds{ wager, funds: self.funds })?;
+        // every win of the round must fit in the counters, as the cards
+        // are already dealt when the prizes are paid
+        let mut jackpot = self.jackpot.clone();
+        jackpot.contribute(wager);
+        let win = self.wager(self.paytable.max_payout(bet)?)?;
+        win.checked_add(jackpot.pool()).ok_or(GameError::Overflow)?;
+        funds.checked_add(jackpot.pool()).ok_or(GameError::Overflow)?;
+
+        self.deck.shuffle_deck();
+        self.reset_selections();
+        self.deal()?;
+        self.bet_amount = bet;
+        self.funds = funds;
+        self.jackpot.contribute(wager);
+        self.state = GameState::HandSelection;
+        Ok(vec![
+            GameEvent::RoundStarted{ round: self.round, bet, funds: self.funds },
+            GameEvent::Dealt{ cards: self.hand.cards.map(|card| card.unwrap()) },
+        ])
+    }
+
+    /// Holds or releases the card in the slot.
+    pub fn toggle_hold(&mut self, slot: usize)->GameResult{
+        self.require(GameState::HandSelection, "hold cards")?;
+        if slot >= self.selected.len(){
+            return Err(GameError::InvalidSlot(slot));
+        }
+        self.selected[slot] = !self.selected[slot];
+        Ok(vec![GameEvent::HoldToggled{ slot, held: self.selected[slot] }])
+    }
+
+    /// Replaces the cards not held and puts the win on the win meter.
+    pub fn draw(&mut self)->GameResult{
+        self.require(GameState::HandSelection, "draw")?;
+        let needed = self.selected.iter().filter(|&&held| !held).count();
+        if self.deck.cards.len() < needed{
+            return Err(GameError::DeckExhausted);
+        }
+        let initial = self.hand.cards;
+        // every extra hand draws from its own copy of the deck
+        for (hand, deck) in self.extra_hands.iter_mut().zip(&mut self.extra_decks){
+            deck.cards.clone_from(&self.deck.cards);
+            deck.shuffle_deck();
+            for (i, slot) in hand.cards.iter_mut().enumerate(){
+                *slot = if self.selected[i]{
+                    initial[i]
+                } else {
+                    deck.deal()
+                };
+            }
+        }
+        self.deal()?;
+
+        // check wins
+        let mut records = vec![self.history_record(&initial, &self.hand)?];
+        for (i, hand) in self.extra_hands.iter().enumerate(){
+            let mut record = self.history_record(&initial, hand)?;
+            record.hand = i as u32 + 1;
+            records.push(record);
+        }
+        self.latest_prize = records[0].prize;
+        // checked to fit when the round started
+        self.latest_payout = records.iter()
+        .try_fold(0u32, |sum, record| sum.checked_add(record.payout)).ok_or(GameError::Overflow)?;
+        self.pending_win = self.latest_payout;
+        self.state = GameState::PayOut;
+
+        // the whole jackpot on the top prize at max bet
+        let mut jackpot = 0;
+        for record in &mut records{
+            if record.prize.is_some() && record.prize == self.paytable.top_prize()
+            && self.bet_amount == self.rules.max_bet(){
+                record.jackpot = self.jackpot.take();
+                jackpot += record.jackpot;
+            }
+        }
+        self.funds = self.funds.checked_add(jackpot).ok_or(GameError::Overflow)?;
+        self.update_peak_funds();
+        let win = self.latest_payout.checked_add(jackpot).ok_or(GameError::Overflow)?;
+        self.biggest_payout = self.biggest_payout.max(win);
+        let mut events: Vec<GameEvent> = records.into_iter().map(GameEvent::Drawn).collect();
+        if jackpot > 0{
+            events.push(GameEvent::JackpotWon{ amount: jackpot, funds: self.funds });
+        }
+        Ok(events)
+    }
+
+    /// Collects what is left on the win meter and moves on to the next
+    /// round, or to game over when the funds do not cover the smallest bet.
+    pub fn end_round(&mut self)->GameResult{
+        self.require(GameState::PayOut, "end the round")?;
+        let mut events = if self.pending_win > 0{ self.collect()? } else { Vec::new() };
+        if self.funds < self.rules.min_bet(){
+            self.state = GameState::GameOver;
+            events.push(GameEvent::GameOver{ round: self.round });
+        }
+        else{
+            events.push(GameEvent::RoundEnded{ round: self.round });
+            self.state = GameState::Betting;
+            self.latest_prize = None;
+            self.latest_payout = 0;
+            self.round += 1;
+            self.reset_deck_and_hand();
+        }
+        Ok(events)
+    }
+
+    /// Returns true, if the pending win can be doubled once more.
+    pub fn can_double_up(&self)->bool{
+        self.pending_win > 0 && self.pending_win <= DOUBLE_UP_LIMIT / 2
+    }
+
+    /// Enters the double up round with the pending win.
+    pub fn start_double_up(&mut self)->GameResult{
+        self.require(GameState::PayOut, "double up")?;
+        if !self.can_double_up(){
+            return Err(GameError::IllegalAction{ action: "double up this win", state: self.state });
+        }
+        self.state = GameState::DoubleUp;
+        Ok(vec![GameEvent::DoubleUpStarted{ stake: self.pending_win }])
+    }
+
+    /// Leaves the double up round without guessing.
+    pub fn cancel_double_up(&mut self)->GameResult{
+        self.require(GameState::DoubleUp, "leave double up")?;
+        self.state = GameState::PayOut;
+        Ok(vec![GameEvent::DoubleUpCancelled])
+    }
+
+    /// Risks the pending win on a guess against the next card from the deck.
+    /// A right guess doubles the win, a wrong one loses it.
+    pub fn double_up(&mut self, guess: DoubleUpGuess)->GameResult{
+        self.require(GameState::DoubleUp, "guess")?;
+        if !self.can_double_up(){
+            return Err(GameError::IllegalAction{ action: "double up this win", state: self.state });
+        }
+        let card = self.deck.deal().ok_or(GameError::DeckExhausted)?;
+        if let Some(card) = self.double_up_card.take(){
+            self.discarded.push(card);
+        }
+        let won = guess.wins(&card);
+        if won{
+            self.pending_win *= 2;
+        }
+        else{
+            self.pending_win = 0;
+        }
+        self.double_up_card = Some(card);
+        self.state = GameState::PayOut;
+        Ok(vec![GameEvent::DoubleUpResolved{ card, won, pending_win: self.pending_win }])
+    }
+
+    /// Moves the whole pending win to funds.
+    pub fn collect(&mut self)->GameResult{
+        self.collect_amount(self.pending_win)
+    }
+
+    /// Moves half of the pending win to funds, the rest stays on the table.
+    pub fn collect_half(&mut self)->GameResult{
+        self.collect_amount(self.pending_win / 2)
+    }
+
+    /// Moves the amount from the win meter to funds.
+    fn collect_amount(&mut self, amount: u32)->GameResult{
+        self.require(GameState::PayOut, "collect")?;
+        if amount == 0{
+            return Err(GameError::IllegalAction{ action: "collect without a win", state: self.state });
+        }
+        self.funds = self.funds.checked_add(amount).ok_or(GameError::Overflow)?;
+        self.update_peak_funds();
+        self.pending_win -= amount;
+        Ok(vec![GameEvent::Collected{ amount, funds: self.funds }])
+    }
+
+    fn update_peak_funds(&mut self){
+        self.peak_funds = self.peak_funds.max(self.funds.saturating_sub(self.bought_credits));
+    }
+
+    /// Sets the value of a credit, in a new game or with an empty credit meter.
+    pub fn set_denomination(&mut self, denomination: Denomination)->GameResult{
+        if self.state != GameState::Betting && self.state != GameState::GameOver{
+            return Err(GameError::IllegalAction{ action: "change the denomination", state: self.state });
+        }
+        // nothing but the starting credits in a new game
+        let starting_funds = self.rules.starting_funds;
+        let fresh = self.round == 1 && self.funds == starting_funds && self.cashed_out == 0
+        && self.cashed_in == self.denomination.value(starting_funds);
+        if fresh{
+            // the starting credits are worth the new value
+            self.cashed_in = denomination.value(self.funds);
+        }
+        else if self.funds != 0{
+            return Err(GameError::IllegalAction{ action: "change the denomination with credits",
+            state: self.state });
+        }
+        self.denomination = denomination;
+        Ok(vec![GameEvent::DenominationChanged{ denomination }])
+    }
+
+    /// Adds credits bought with money, which do not count for the score.
+    /// Allowed between rounds, and after game over to start a new game
+    /// with what was left.
+    pub fn cash_in(&mut self, credits: u32)->GameResult{
+        if self.state != GameState::Betting && self.state != GameState::GameOver{
+            return Err(GameError::IllegalAction{ action: "cash in", state: self.state });
+        }
+        if credits == 0{
+            return Err(GameError::IllegalAction{ action: "cash in nothing", state: self.state });
+        }
+        let funds = self.funds.checked_add(credits).ok_or(GameError::Overflow)?;
+        let mut events = Vec::new();
+        if self.state == GameState::GameOver{
+            self.start_game(self.funds);
+            events.push(GameEvent::NewGame);
+        }
+        self.funds = funds;
+        self.bought_credits = self.bought_credits.saturating_add(credits);
+        self.cashed_in += self.denomination.value(credits);
+        events.push(GameEvent::CashedIn{ credits, funds: self.funds });
+        Ok(events)
+    }
+
+    /// Pays out every credit on the meter, which ends the game. Cash in
+    /// to play on.
+    pub fn cash_out(&mut self)->GameResult{
+        self.require(GameState::Betting, "cash out")?;
+        if self.funds == 0{
+            return Err(GameError::IllegalAction{ action: "cash out without credits", state: self.state });
+        }
+        let credits = std::mem::take(&mut self.funds);
+        let cents = self.denomination.value(credits);
+        self.cashed_out += cents;
+        self.state = GameState::GameOver;
+        Ok(vec![GameEvent::CashedOut{ credits, cents }, GameEvent::GameOver{ round: self.round }])
+    }
+
+    /// Expected payout of every hold choice of the dealt hand, indexed
+    /// by a bit mask of the held slots.
+    pub fn hold_values(&self)->Result<[f64; 32], GameError>{
+        self.require(GameState::HandSelection, "value the holds")?;
+        Ok(self.hand.hold_values(&self.deck.cards, &self.paytable, self.bet_amount))
+    }
+
+    fn reset_selections(&mut self){
+        for i in 0..self.selected.len(){
+            self.selected[i] = false;
+        }
+    }
+
+    /// Raises the bet to the next allowed bet the funds cover, then starts
+    /// over from the smallest.
+    pub fn cycle_bet_amount(&mut self)->GameResult{
+        self.require(GameState::Betting, "change the bet")?;
+        let raised = self.rules.bets.iter().copied()
+        .find(|&bet| bet > self.bet_amount);
+        match raised{
+            Some(bet) if self.wager(bet).is_ok_and(|wager| wager <= self.funds) =>{
+                self.bet_amount = bet;
+            }
+            _ =>{
+                self.bet_amount = self.rules.min_bet();
+            }
+        }
+        Ok(vec![GameEvent::BetChanged{ bet: self.bet_amount }])
+    }
+
+    // fn query_quit(&mut self, input: &String){
+    //     let input_lowercase = input.to_lowercase();
+    //     match input_lowercase.as_str(){
+    //         "quit" | "exit" =>{
+    //             println!("Quitting.");
+    //             self.playing = false;
+    //         }
+    //         _ => {
+
+    //         }
+    //     }
+    // }
+
+    // fn bet_money(&mut self, bet_amount: u32){
+    //     if bet_amount >= 1 && bet_amount <= 100
+    //     && self.funds >= bet_amount{
+    //         self.bet_amount = bet_amount;
+    //         self.funds -= bet_amount;
+    //         self.state = GameState::HandSelection;
+    //     } else{
+    //         println!("Invaid bet!")
+    //     }
+    // }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+    use crate::card::Suit;
+
+    #[test]
+    fn deal(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        // hand must be empty before deal
+        for option_card in &game.hand.cards{
+            if option_card.is_some(){
+                panic!("Cards in hand before deal");
+            }
+        }
+        // has_nones
+        //assert!(game.hand.has_nones());
+
+        let deck_len_before_deal = game.deck.cards.len();
+        // deal
+        game.deal().unwrap();
+        //assert!(!game.hand.has_nones());
+
+        let deck_len_after_deal = game.deck.cards.len();
+        assert_eq!(deck_len_before_deal - deck_len_after_deal, game.hand.cards.len());
+        assert_eq!(game.discarded.len(), 0);
+        
+        game.selected[2] = true;
+        game.selected[3] = true;
+
+        game.deal().unwrap();
+        // discarding from hand
+        assert_eq!(game.discarded.len(), game.hand.cards.len() - 2);
+        let deck_len_after_hand_selection_deal = game.deck.cards.len();
+        assert_eq!(deck_len_after_deal - deck_len_after_hand_selection_deal, game.hand.cards.len()-2);
+    }
+    #[test]
+    fn double_up(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.state = GameState::PayOut;
+        game.pending_win = 40;
+        game.funds = 80;
+
+        game.start_double_up().unwrap();
+        game.deck.cards.insert(0, Card::new(Rank::Three, Suit::Spades));
+        game.double_up(DoubleUpGuess::Small).unwrap();
+        assert_eq!(game.pending_win, 80);
+        assert!(game.state == GameState::PayOut);
+
+        // jokers always win
+        game.start_double_up().unwrap();
+        game.deck.cards.insert(0, Card::Joker);
+        game.double_up(DoubleUpGuess::Large).unwrap();
+        assert_eq!(game.pending_win, 160);
+        assert_eq!(game.discarded.len(), 1);
+
+        // no doubling without entering the double up round
+        assert!(game.double_up(DoubleUpGuess::Large).is_err());
+        assert_eq!(game.pending_win, 160);
+
+        game.collect().unwrap();
+        assert_eq!(game.funds, 240);
+        assert_eq!(game.pending_win, 0);
+    }
+    #[test]
+    fn double_up_lose(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.state = GameState::PayOut;
+        game.pending_win = 40;
+
+        // sevens always lose
+        game.start_double_up().unwrap();
+        game.deck.cards.insert(0, Card::new(Rank::Seven, Suit::Spades));
+        game.double_up(DoubleUpGuess::Large).unwrap();
+        assert_eq!(game.pending_win, 0);
+        assert!(game.state == GameState::PayOut);
+
+        // the limit stops doubling
+        game.pending_win = DOUBLE_UP_LIMIT;
+        assert_eq!(game.start_double_up(), Err(GameError::IllegalAction{
+            action: "double up this win", state: GameState::PayOut }));
+        assert!(game.state == GameState::PayOut);
+    }
+    #[test]
+    fn collect_half(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.state = GameState::PayOut;
+        game.funds = 0;
+        game.pending_win = 75;
+
+        game.collect_half().unwrap();
+        assert_eq!(game.funds, 37);
+        assert_eq!(game.pending_win, 38);
+
+        game.collect().unwrap();
+        assert_eq!(game.funds, 75);
+        assert_eq!(game.pending_win, 0);
+
+        // an odd win halved twice leaves less than the smallest bet
+        game.funds = 0;
+        game.pending_win = 21;
+        game.collect_half().unwrap();
+        game.start_double_up().unwrap();
+        game.deck.cards.insert(0, Card::new(Rank::Seven, Suit::Hearts));
+        game.double_up(DoubleUpGuess::Small).unwrap();
+        assert_eq!(game.funds, 10);
+        assert_eq!(game.end_round(), Ok(vec![GameEvent::GameOver{ round: 1 }]));
+        assert!(game.state == GameState::GameOver);
+    }
+    #[test]
+    fn engine_round(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+
+        // actions not allowed in the state change nothing
+        assert_eq!(game.draw(), Err(GameError::IllegalAction{ action: "draw", state: GameState::Betting }));
+        assert_eq!(game.hold_values(), Err(GameError::IllegalAction{
+            action: "value the holds", state: GameState::Betting }));
+        assert!(game.collect().is_err());
+        assert_eq!(game.start_round(120), Err(GameError::InvalidBet(120)));
+        assert_eq!(game.start_round(0), Err(GameError::InvalidBet(0)));
+        let mut big = Paytable::classic();
+        big.rows[0].multiplier = 50_000_000;
+        let mut overflowing = JokeriPokeri::new(big, 0);
+        assert_eq!(overflowing.start_round(100), Err(GameError::Overflow));
+        // the wins of every hand together, and the jackpot on top of them
+        overflowing.funds = 1000;
+        overflowing.set_hands(5).unwrap();
+        assert_eq!(overflowing.start_round(20), Err(GameError::Overflow));
+        overflowing.set_hands(1).unwrap();
+        overflowing.jackpot = Jackpot::new(100);
+        overflowing.jackpot.contribute(u32::MAX);
+        assert_eq!(overflowing.start_round(20), Err(GameError::Overflow));
+        assert_eq!(overflowing.funds, 1000);
+        overflowing.jackpot = Jackpot::new(1);
+        assert!(overflowing.start_round(20).is_ok());
+        game.funds = 30;
+        assert_eq!(game.start_round(40), Err(GameError::InsufficientFunds{ wager: 40, funds: 30 }));
+        assert!(game.hand.cards.iter().all(|card| card.is_none()));
+        game.funds = 100;
+
+        let events = game.start_round(40).unwrap();
+        assert_eq!(events[0], GameEvent::RoundStarted{ round: 1, bet: 40, funds: 60 });
+        assert!(matches!(events[1], GameEvent::Dealt{ .. }));
+
+        game.hand.cards = [
+            Some(Card::new(Rank::Five, Suit::Spades)),
+            Some(Card::new(Rank::Five, Suit::Hearts)),
+            Some(Card::new(Rank::Two, Suit::Diamonds)),
+            Some(Card::new(Rank::Nine, Suit::Clubs)),
+            Some(Card::new(Rank::King, Suit::Spades)),
+        ];
+        game.deck.cards.insert(0, Card::new(Rank::Five, Suit::Diamonds));
+        game.deck.cards.insert(1, Card::new(Rank::Three, Suit::Clubs));
+        game.deck.cards.insert(2, Card::new(Rank::Eight, Suit::Hearts));
+        assert_eq!(game.toggle_hold(0), Ok(vec![GameEvent::HoldToggled{ slot: 0, held: true }]));
+        game.toggle_hold(1).unwrap();
+        assert_eq!(game.toggle_hold(5), Err(GameError::InvalidSlot(5)));
+
+        match &game.draw().unwrap()[..]{
+            [GameEvent::Drawn(record)]=>{
+                assert_eq!(record.held, [true, true, false, false, false]);
+                assert_eq!(record.prize, Some(Prize::ThreeOfAKind));
+                assert_eq!(record.payout, 80);
+            }
+            events=>{ panic!("unexpected events {:?}", events); }
+        }
+        assert_eq!(game.end_round(), Ok(vec![
+            GameEvent::Collected{ amount: 80, funds: 140 },
+            GameEvent::RoundEnded{ round: 1 },
+        ]));
+        assert_eq!((game.peak_funds, game.biggest_payout), (140, 80));
+        assert!(game.state == GameState::Betting);
+        assert_eq!(game.round, 2);
+
+        // losing the last funds ends the game
+        game.funds = 20;
+        game.start_round(20).unwrap();
+        for (i, card) in [
+            Card::new(Rank::Two, Suit::Spades),
+            Card::new(Rank::Four, Suit::Hearts),
+            Card::new(Rank::Six, Suit::Diamonds),
+            Card::new(Rank::Nine, Suit::Clubs),
+            Card::new(Rank::Jack, Suit::Spades),
+        ].into_iter().enumerate(){
+            game.deck.cards.insert(i, card);
+        }
+        game.draw().unwrap();
+        assert_eq!(game.end_round(), Ok(vec![GameEvent::GameOver{ round: 2 }]));
+        assert!(game.state == GameState::GameOver);
+        assert!(game.start_round(20).is_err());
+    }
+    #[test]
+    fn deck_exhausted(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.start_round(20).unwrap();
+        let remaining: Vec<Card> = game.deck.cards.drain(3..).collect();
+        // three cards do not replace five
+        assert_eq!(game.draw(), Err(GameError::DeckExhausted));
+        assert!(game.state == GameState::HandSelection);
+        for slot in 0..2{
+            game.toggle_hold(slot).unwrap();
+        }
+        game.draw().unwrap();
+        game.pending_win = 40;
+        game.start_double_up().unwrap();
+        assert_eq!(game.double_up(DoubleUpGuess::Small), Err(GameError::DeckExhausted));
+        assert_eq!(game.pending_win, 40);
+        game.deck.cards = remaining;
+        assert!(game.double_up(DoubleUpGuess::Small).is_ok());
+    }
+    #[test]
+    fn cash_in_and_out(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.set_denomination(Denomination::Cents50).unwrap();
+        assert_eq!(game.cashed_in, 5000);
+
+        assert_eq!(game.cash_in(20), Ok(vec![GameEvent::CashedIn{ credits: 20, funds: 120 }]));
+        assert_eq!(game.cashed_in, 6000);
+        // credits on the meter keep their value
+        assert!(game.set_denomination(Denomination::Euro1).is_err());
+
+        assert_eq!(game.cash_out(), Ok(vec![
+            GameEvent::CashedOut{ credits: 120, cents: 6000 },
+            GameEvent::GameOver{ round: 1 },
+        ]));
+        assert_eq!((game.funds, game.cashed_out), (0, 6000));
+        assert!(game.state == GameState::GameOver);
+        assert!(game.cash_out().is_err());
+        assert!(game.start_round(20).is_err());
+        game.set_denomination(Denomination::Euro1).unwrap();
+
+        // cashing in after game over starts a new game
+        assert_eq!(game.cash_in(10), Ok(vec![
+            GameEvent::NewGame,
+            GameEvent::CashedIn{ credits: 10, funds: 10 },
+        ]));
+        assert_eq!(game.cashed_in, 7000);
+        assert!(game.state == GameState::Betting);
+        game.cash_in(5).unwrap();
+        assert_eq!((game.funds, game.round), (15, 1));
+        game.start_round(20).unwrap_err();
+        assert!(game.cash_in(5).is_ok());
+        game.start_round(20).unwrap();
+        assert!(game.cash_in(5).is_err());
+        // bought credits are no score
+        assert_eq!(game.peak_funds, 0);
+        game.state = GameState::PayOut;
+        game.pending_win = 40;
+        game.collect().unwrap();
+        assert_eq!((game.funds, game.peak_funds), (40, 20));
+    }
+    #[test]
+    fn custom_rules(){
+        let rules = Rules{ starting_funds: 50, bets: vec![5, 10, 25], jokers: 0 };
+        let mut game = JokeriPokeri::with_rules(Paytable::classic(), rules, 1);
+        assert_eq!(game.deck.cards.len(), 52);
+        assert_eq!((game.funds, game.bet_amount), (50, 5));
+        assert_eq!(game.start_round(20), Err(GameError::InvalidBet(20)));
+
+        let mut bets = Vec::new();
+        for _i in 0..4{
+            game.cycle_bet_amount().unwrap();
+            bets.push(game.bet_amount);
+        }
+        assert_eq!(bets, [10, 25, 5, 10]);
+        // only the bets the funds cover
+        game.set_hands(3).unwrap();
+        game.cycle_bet_amount().unwrap();
+        assert_eq!(game.bet_amount, 5);
+        game.set_hands(1).unwrap();
+
+        // only after game over, the credits left are kept
+        game.start_round(5).unwrap();
+        assert_eq!(game.reset_game(), Err(GameError::IllegalAction{
+            action: "start a new game", state: GameState::HandSelection }));
+        assert_eq!(game.funds, 45);
+        game.state = GameState::GameOver;
+        game.funds = 3;
+        game.reset_game().unwrap();
+        assert_eq!((game.funds, game.bet_amount, game.round), (53, 5, 1));
+    }
+    #[test]
+    fn jackpot(){
+        let royal = [
+            Card::new(Rank::Ten, Suit::Hearts),
+            Card::new(Rank::Jack, Suit::Hearts),
+            Card::new(Rank::Queen, Suit::Hearts),
+            Card::new(Rank::King, Suit::Hearts),
+            Card::new(Rank::Ace, Suit::Hearts),
+        ];
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        game.jackpot = Jackpot::new(10);
+        game.funds = 1000;
+
+        // not paid below the max bet
+        game.start_round(80).unwrap();
+        assert_eq!(game.jackpot.pool(), 8);
+        game.hand.cards = royal.map(Some);
+        for slot in 0..5{
+            game.toggle_hold(slot).unwrap();
+        }
+        let events = game.draw().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(game.jackpot.pool(), 8);
+        game.end_round().unwrap();
+
+        game.start_round(game.rules.max_bet()).unwrap();
+        game.hand.cards = royal.map(Some);
+        for slot in 0..5{
+            game.toggle_hold(slot).unwrap();
+        }
+        let events = game.draw().unwrap();
+        assert!(matches!(&events[0], GameEvent::Drawn(record) if record.jackpot == 18));
+        let funds = 1000 - 80 + 8000 - 100 + 18;
+        assert_eq!(events[1], GameEvent::JackpotWon{ amount: 18, funds });
+        assert_eq!(game.funds, funds);
+        assert_eq!(game.pending_win, 100 * 100);
+        assert_eq!(game.jackpot.pool(), 0);
+
+        // the jackpot is not reset with the game
+        game.jackpot.contribute(100);
+        game.state = GameState::GameOver;
+        game.reset_game().unwrap();
+        assert_eq!(game.jackpot.pool(), 10);
+    }
+    #[test]
+    fn multi_hand(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 5);
+        assert_eq!(game.set_hands(0), Err(GameError::InvalidHands(0)));
+        assert_eq!(game.set_hands(10), Ok(vec![GameEvent::HandsChanged{ hands: 10 }]));
+        // the bet of every hand must be covered
+        assert_eq!(game.start_round(20), Err(GameError::InsufficientFunds{ wager: 200, funds: 100 }));
+        game.set_hands(3).unwrap();
+        assert_eq!(game.extra_decks.len(), 2);
+        game.start_round(20).unwrap();
+        assert_eq!(game.funds, 40);
+
+        let kings = [
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::King, Suit::Hearts),
+            Card::new(Rank::King, Suit::Diamonds),
+            Card::new(Rank::King, Suit::Clubs),
+        ];
+        for (slot, card) in kings.iter().enumerate(){
+            let dealt = game.hand.cards[slot].replace(*card).unwrap();
+            // keep every card once in the game
+            let i = game.deck.cards.iter().position(|other| other == card).unwrap();
+            game.deck.cards[i] = dealt;
+            game.toggle_hold(slot).unwrap();
+        }
+        let dealt = game.hand.cards;
+
+        let events = game.draw().unwrap();
+        assert_eq!(events.len(), 3);
+        let mut total = 0;
+        for (i, event) in events.iter().enumerate(){
+            match event{
+                GameEvent::Drawn(record)=>{
+                    assert_eq!(record.hand, i as u32);
+                    assert!(record.prize == Some(Prize::FourOfAKind)
+                    || record.prize == Some(Prize::FiveOfAKind));
+                    total += record.payout;
+                }
+                event=>{ panic!("unexpected event {:?}", event); }
+            }
+        }
+        assert_eq!(game.pending_win, total);
+        for hand in &game.extra_hands{
+            assert_eq!(hand.cards[..4], game.hand.cards[..4]);
+            // drawn from the deck left after the deal
+            assert!(!dealt.contains(&hand.cards[4]));
+        }
+
+        game.end_round().unwrap();
+        assert!(game.extra_hands.iter().all(|hand| hand.cards == [None; 5]));
+        assert_eq!(game.deck.cards.len(), 54);
+    }
+    #[test]
+    fn save_and_load(){
+        let path = crate::persist::temp_path("save.json");
+        let mut game = JokeriPokeri::new(Paytable::classic(), 7);
+        game.deal().unwrap();
+        game.selected[1] = true;
+        game.deal().unwrap();
+        game.funds = 60;
+        game.round = 4;
+        game.state = GameState::HandSelection;
+        game.save(&path).unwrap();
+
+        let mut loaded = JokeriPokeri::load(&path).unwrap();
+        assert_eq!(loaded.hand.cards, game.hand.cards);
+        assert_eq!(loaded.deck.cards, game.deck.cards);
+        assert_eq!(loaded.discarded, game.discarded);
+        assert_eq!(loaded.selected, game.selected);
+        assert_eq!((loaded.funds, loaded.round, loaded.seed), (60, 4, 7));
+        assert!(loaded.state == GameState::HandSelection);
+
+        // the generator continues where it was
+        game.reset_deck_and_hand();
+        loaded.reset_deck_and_hand();
+        assert_eq!(loaded.deck.cards, game.deck.cards);
+
+        let text = std::fs::read_to_string(&path).unwrap()
+        .replace(&format!("\"version\":{}", SAVE_VERSION), "\"version\":99");
+        std::fs::write(&path, text).unwrap();
+        assert!(JokeriPokeri::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+    #[test]
+    fn reset_deck_and_hand(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 0);
+        let deck_size_before_deal = game.deck.cards.len();
+        game.deal().unwrap();
+        game.selected[1] = true;
+        game.selected[2] = true;
+        game.deal().unwrap();
+        game.reset_deck_and_hand();
+        assert_eq!(deck_size_before_deal, game.deck.cards.len());
+        assert_eq!(game.discarded.len(), 0);
+    }
+    #[test]
+    fn seeded_deals(){
+        let mut game = JokeriPokeri::new(Paytable::classic(), 42);
+        let mut same_seed = JokeriPokeri::new(Paytable::classic(), 42);
+        for _round in 0..3{
+            game.deal().unwrap();
+            same_seed.deal().unwrap();
+            assert_eq!(game.hand.cards, same_seed.hand.cards);
+            game.reset_deck_and_hand();
+            same_seed.reset_deck_and_hand();
+        }
+
+        let other_seed = Deck::new(43);
+        assert_ne!(game.deck.cards, other_seed.cards);
+    }
+    #[test]
+    fn hand_history(){
+        let path = crate::persist::temp_path("history.jsonl");
+        let mut game = JokeriPokeri::new(Paytable::classic(), 3);
+        game.deal().unwrap();
+        let initial = game.hand.cards;
+        game.selected[0] = true;
+        game.selected[3] = true;
+        game.deal().unwrap();
+        game.latest_prize = game.hand.evaluate();
+        let record = game.history_record(&initial, &game.hand).unwrap();
+        assert_eq!(record.replacements.len(), 3);
+        assert_eq!(record.final_cards().map(Some), game.hand.cards);
+
+        record.append(&path).unwrap();
+        game.round += 1;
+        game.history_record(&initial, &game.hand).unwrap().append(&path).unwrap();
+        let records = HandRecord::read_all(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], record);
+        assert_eq!(records[1].round, 2);
+
+        // a truncated record is refused instead of replayed
+        let mut truncated = record.clone();
+        truncated.replacements.pop();
+        truncated.append(&path).unwrap();
+        let error = HandRecord::read_all(&path).unwrap_err();
+        assert!(error.ends_with("line 3: 2 replacements for 3 cards not held"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,831 @@
+//! Hand evaluation and the prizes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::card::{Card, Suit};
+use crate::paytable::Paytable;
+
+/// Natural cards of a full hand counted by value, and the jokers
+/// separately. All prize detection works on these counts.
+pub(crate) struct HandCounts{
+    /// Natural cards by value, index 0 is unused
+    pub(crate) values: [u8; 14],
+    jokers: u8,
+    /// True, if all the natural cards share a suit
+    one_suit: bool,
+}
+impl HandCounts{
+    /// Returns None, if any of the card slots are empty.
+    fn new(cards: &[Option<Card>; 5])->Option<Self>{
+        let mut counts = HandCounts{
+            values: [0; 14],
+            jokers: 0,
+            one_suit: true,
+        };
+        let mut first_suit: Option<Suit> = None;
+        for element in cards{
+            let (rank, suit) = match element.as_ref()?{
+                Card::Natural{ rank, suit }=>{ (rank, suit) }
+                Card::Joker=>{
+                    // joker takes the value and suit of whatever is needed
+                    counts.jokers += 1;
+                    continue;
+                }
+            };
+            counts.values[rank.value() as usize] += 1;
+            match first_suit{
+                Some(first) if first != *suit=>{
+                    counts.one_suit = false;
+                }
+                _=>{
+                    first_suit = Some(*suit);
+                }
+            }
+        }
+        Some(counts)
+    }
+
+    /// Returns the biggest group of same valued cards, jokers included.
+    fn largest_group(&self)->u8{
+        self.values.iter().max().unwrap() + self.jokers
+    }
+
+    fn is_full_house(&self)->bool{
+        // jokers can fill the groups, as long as there are at most two
+        // kinds of values and neither group is bigger than three
+        let kinds = self.values.iter().filter(|&&x| x > 0).count();
+        kinds <= 2 && *self.values.iter().max().unwrap() <= 3
+    }
+
+    fn is_straight(&self)->bool{
+        // natural cards must all have different values and fit in a
+        // range of five, jokers fill the gaps
+        let mut lowest: usize = 0;
+        let mut highest: usize = 0;
+        for (value, &count) in self.values.iter().enumerate(){
+            if count > 1{
+                return false;
+            }
+            if count == 1{
+                if lowest == 0{
+                    lowest = value;
+                }
+                highest = value;
+            }
+        }
+        if highest - lowest <= 4{
+            return true;
+        }
+        // an ace also counts above the king, the rest must then be
+        // tens or higher. No wrapping around from king to two.
+        self.values[1] == 1 && self.values[2..10].iter().all(|&count| count == 0)
+    }
+
+    fn is_two_pairs(&self)->bool{
+        let pairs = self.values.iter().filter(|&&x| x >= 2).count() as u8;
+        let singles = self.values.iter().filter(|&&x| x == 1).count() as u8;
+        // a joker pairs up with a single card, two leftover jokers
+        // make a pair of their own
+        let joker_pairs = self.jokers.min(singles);
+        pairs + joker_pairs + (self.jokers - joker_pairs) / 2 >= 2
+    }
+
+    fn is_royal(&self)->bool{
+        // a straight flush of the ace and the tens to kings,
+        // the jokers filling in for any of them
+        self.one_suit && self.is_straight()
+        && self.values[2..10].iter().all(|&count| count == 0)
+    }
+
+    fn is_prize(&self, prize: Prize)->bool{
+        match prize{
+            Prize::RoyalFlush => self.jokers == 0 && self.is_royal(),
+            Prize::WildRoyalFlush => self.is_royal(),
+            Prize::FiveOfAKind => self.largest_group() >= 5,
+            Prize::StraightFlush => self.is_straight() && self.one_suit,
+            Prize::FourOfAKind => self.largest_group() >= 4,
+            Prize::FullHouse => self.is_full_house(),
+            Prize::Flush => self.one_suit,
+            Prize::Straight => self.is_straight(),
+            Prize::ThreeOfAKind => self.largest_group() >= 3,
+            Prize::TwoPairs => self.is_two_pairs(),
+        }
+    }
+}
+
+/// Five card slots, empty between rounds.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Hand{
+    //cards: Vec<Card>,
+    /// The card in each slot, None for an empty slot
+    pub cards: [Option<Card>; 5],
+    //selections: Vec<u8>,
+}
+impl Hand{
+    /// Creates a hand with every slot empty.
+    pub fn new()->Self{
+        Self { 
+            cards: [None, None, None, None, None],
+            //selections: Vec::new(),
+        }
+    }
+
+    // Returns true, if any of the card slots are None
+    // fn has_nones(&self)->bool{
+    //     for element in &self.cards{
+    //         match element{
+    //             Some(_)=>{ continue; }
+    //             None=>{
+    //                 return true
+    //             }
+    //         }
+    //     }
+    //     return false
+    // }
+
+    /// Counts the natural cards in hand by value and the jokers separately.
+    /// Returns None, if any of the card slots are empty.
+    pub(crate) fn value_counts(&self)->Option<HandCounts>{
+        HandCounts::new(&self.cards)
+    }
+
+    /// Returns the best prize the hand wins, or None if the hand
+    /// does not win anything.
+    pub fn evaluate(&self)->Option<Prize>{
+        let counts = self.value_counts()?;
+        Prize::ALL.into_iter().find(|&prize| counts.is_prize(prize))
+    }
+
+    /// Returns which of the card slots make up the best prize of the hand.
+    /// Every slot is false, if the hand does not win anything.
+    pub fn winning_cards(&self)->[bool; 5]{
+        let mut winning = [false; 5];
+        let prize = match self.evaluate(){
+            Some(prize)=>{ prize }
+            None=>{ return winning; }
+        };
+        let counts = self.value_counts().unwrap().values;
+        for (i, element) in self.cards.iter().enumerate(){
+            let rank = match element.as_ref().unwrap(){
+                Card::Natural{ rank, .. }=>{ rank }
+                Card::Joker=>{
+                    winning[i] = true;
+                    continue;
+                }
+            };
+            winning[i] = match prize{
+                Prize::FiveOfAKind | Prize::FourOfAKind | Prize::ThreeOfAKind =>{
+                    // the biggest group of values, the higher value on a tie
+                    let group_value = (1..=13u8)
+                    .max_by_key(|&v| counts[v as usize]).unwrap();
+                    rank.value() == group_value
+                }
+                Prize::TwoPairs =>{
+                    counts[rank.value() as usize] >= 2
+                }
+                _ =>{
+                    true
+                }
+            };
+        }
+        winning
+    }
+
+    /// Exact expected payout of every hold choice, when the slots not held
+    /// are drawn from the remaining cards. Indexed by a bit mask of the held
+    /// slots, bit 0 being the first slot. The hand must be full.
+    pub fn hold_values(&self, remaining: &[Card], paytable: &Paytable, bet: u32)->[f64; 32]{
+        let cards = self.cards.map(|card| card.expect("hold values need a full hand"));
+        let payouts = Prize::ALL.map(|prize| paytable.payout_wide(prize, bet));
+        let mut values = [0.0; 32];
+        for (mask, value) in values.iter_mut().enumerate(){
+            let draws = (0..5).filter(|i| mask & (1 << i) == 0).count();
+            let mut hand = Hand::new();
+            let mut total: u64 = 0;
+            let mut count: u64 = 0;
+            for_each_combination(remaining, draws, |drawn|{
+                let mut drawn = drawn.iter();
+                for (i, slot) in hand.cards.iter_mut().enumerate(){
+                    *slot = if mask & (1 << i) != 0{
+                        Some(cards[i])
+                    } else {
+                        drawn.next().copied()
+                    };
+                }
+                if let Some(prize) = hand.evaluate(){
+                    total += payouts[prize as usize];
+                }
+                count += 1;
+            });
+            if count > 0{
+                *value = total as f64 / count as f64;
+            }
+        }
+        values
+    }
+
+    // fn is_wincondition1...
+    // fn is_wincondition2...
+}
+
+/// Checks for a single prize, regardless of the better ones.
+impl Hand{
+    /// Returns true, if the hand has an ace high straight flush without jokers.
+    pub fn is_royal_flush(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::RoyalFlush))
+    }
+
+    /// Returns true, if the hand has an ace high straight flush with jokers.
+    pub fn is_wild_royal_flush(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::WildRoyalFlush))
+    }
+
+    /// Returns true, if the hand has five cards of a rank, jokers included.
+    pub fn is_five_of_a_kind(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::FiveOfAKind))
+    }
+
+    /// Returns true, if the hand has a straight flush.
+    pub fn is_straight_flush(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::StraightFlush))
+    }
+
+    /// Returns true, if the hand has four cards of a rank.
+    pub fn is_four_of_a_kind(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::FourOfAKind))
+    }
+
+    /// Returns true, if the hand has three of a rank and a pair.
+    pub fn is_full_house(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::FullHouse))
+    }
+
+    /// Returns true, if the hand has three cards of a rank.
+    pub fn is_three_of_a_kind(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::ThreeOfAKind))
+    }
+
+    /// Returns true, if the hand has five cards of a suit.
+    pub fn is_flush(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::Flush))
+    }
+
+    /// Returns true, if the hand has five ranks in a row.
+    pub fn is_straight(&self)->bool{
+        // Not enough cards in hand for a straight, if any are missing
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::Straight))
+    }
+
+    /// Returns true, if the hand has two pairs.
+    pub fn is_two_pairs(&self)->bool{
+        self.value_counts().is_some_and(|counts| counts.is_prize(Prize::TwoPairs))
+    }
+}
+
+/// Calls f with every k-card combination of the cards, in order.
+pub fn for_each_combination(cards: &[Card], k: usize, mut f: impl FnMut(&[Card])){
+    if k > cards.len(){
+        return;
+    }
+    let mut indices: Vec<usize> = (0..k).collect();
+    let mut combination: Vec<Card> = indices.iter().map(|&i| cards[i]).collect();
+    loop {
+        f(&combination);
+        // find the rightmost index that can still move right
+        let mut i = k;
+        loop {
+            if i == 0{
+                return;
+            }
+            i -= 1;
+            if indices[i] < cards.len() - k + i{
+                break;
+            }
+            if i == 0{
+                return;
+            }
+        }
+        indices[i] += 1;
+        combination[i] = cards[indices[i]];
+        for j in i + 1..k{
+            indices[j] = indices[j - 1] + 1;
+            combination[j] = cards[indices[j]];
+        }
+    }
+}
+
+/// Converts a bit mask of slots to a selection, bit 0 being the first slot.
+pub fn mask_to_selection(mask: usize)->[bool; 5]{
+    let mut selection = [false; 5];
+    for (i, selected) in selection.iter_mut().enumerate(){
+        *selected = mask & (1 << i) != 0;
+    }
+    selection
+}
+
+/// Converts a selection of slots to a bit mask, bit 0 being the first slot.
+pub fn selection_to_mask(selection: &[bool; 5])->usize{
+    selection.iter().enumerate()
+    .filter(|(_, &selected)| selected)
+    .map(|(i, _)| 1 << i)
+    .sum()
+}
+
+/// The winning hands, from the best to the worst.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum Prize{
+    /// Ässäsuora, ace high straight flush without jokers
+    RoyalFlush,
+    /// Five cards of a rank, only possible with jokers
+    FiveOfAKind,
+    /// Royal flush completed with jokers
+    WildRoyalFlush,
+    /// Five ranks in a row of the same suit
+    StraightFlush,
+    /// Four cards of a rank
+    FourOfAKind,
+    /// Three of a rank and a pair
+    FullHouse,
+    /// Five cards of the same suit
+    Flush,
+    /// Five ranks in a row
+    Straight,
+    /// Three cards of a rank
+    ThreeOfAKind,
+    /// Two pairs of different ranks
+    TwoPairs,
+}
+impl Prize{
+    /// Every prize, from the best to the worst.
+    pub const ALL: [Prize; 10] = [
+        Prize::RoyalFlush,
+        Prize::FiveOfAKind,
+        Prize::WildRoyalFlush,
+        Prize::StraightFlush,
+        Prize::FourOfAKind,
+        Prize::FullHouse,
+        Prize::Flush,
+        Prize::Straight,
+        Prize::ThreeOfAKind,
+        Prize::TwoPairs,
+    ];
+
+    /// Name of the prize as shown on the paytable.
+    pub fn as_str(&self)->&'static str{
+        match self{
+            Prize::RoyalFlush => "Royal flush",
+            Prize::FiveOfAKind => "Five-of-a-kind",
+            Prize::WildRoyalFlush => "Wild royal flush",
+            Prize::StraightFlush => "Straight flush",
+            Prize::FourOfAKind => "Four-of-a-kind",
+            Prize::FullHouse => "Full house",
+            Prize::Flush => "Flush",
+            Prize::Straight => "Straight",
+            Prize::ThreeOfAKind => "Three-of-a-kind",
+            Prize::TwoPairs => "Two pairs",
+        }
+    }
+
+    /// The prize paid instead, when a paytable has no row for this one.
+    /// Royal flushes are straight flushes on tables without them.
+    pub fn fallback(&self)->Option<Prize>{
+        match self{
+            Prize::RoyalFlush => Some(Prize::WildRoyalFlush),
+            Prize::WildRoyalFlush => Some(Prize::StraightFlush),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+    use crate::card::Rank;
+
+    #[test]
+    fn straight(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Three, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Clubs));
+
+        assert!(hand.is_straight());
+
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Spades));
+
+        assert!(!hand.is_straight());
+
+        // joker fills the gap
+        hand.cards[0] = Some(Card::Joker);
+        hand.cards[1] = Some(Card::new(Rank::Six, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Nine, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Seven, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Eight, Suit::Clubs));
+        assert!(hand.is_straight());
+
+        // two jokers at the ends
+        hand.cards[1] = Some(Card::Joker);
+        assert!(hand.is_straight());
+
+        // joker can not fix a pair
+        hand.cards[1] = Some(Card::new(Rank::Nine, Suit::Hearts));
+        assert!(!hand.is_straight());
+
+        // too wide for the joker to fill
+        hand.cards[1] = Some(Card::new(Rank::Queen, Suit::Hearts));
+        assert!(!hand.is_straight());
+
+        // ace high
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Jack, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Queen, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::King, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Ace, Suit::Diamonds));
+        assert!(hand.is_straight());
+
+        // ace high with jokers
+        hand.cards[1] = Some(Card::Joker);
+        assert!(hand.is_straight());
+        hand.cards[0] = Some(Card::Joker);
+        assert!(hand.is_straight());
+
+        // ace low
+        hand.cards[0] = Some(Card::new(Rank::Ace, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Three, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Four, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Five, Suit::Diamonds));
+        assert!(hand.is_straight());
+
+        // ace low with a joker
+        hand.cards[2] = Some(Card::Joker);
+        assert!(hand.is_straight());
+
+        // no wrapping around the ace
+        hand.cards[0] = Some(Card::new(Rank::Queen, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::King, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Ace, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Three, Suit::Diamonds));
+        assert!(!hand.is_straight());
+
+        // not even with jokers
+        hand.cards[0] = Some(Card::Joker);
+        hand.cards[4] = Some(Card::Joker);
+        assert!(!hand.is_straight());
+    }
+    #[test]
+    fn flush(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Six, Suit::Spades));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Spades));
+        hand.cards[3] = Some(Card::new(Rank::King, Suit::Spades));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(hand.is_flush());
+
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Six, Suit::Spades));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Hearts));
+        hand.cards[3] = Some(Card::new(Rank::King, Suit::Spades));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+        
+        assert!(!hand.is_flush());
+
+        // joker takes the suit of the others
+        hand.cards[2] = Some(Card::Joker);
+        assert!(hand.is_flush());
+
+        hand.cards[4] = Some(Card::Joker);
+        assert!(hand.is_flush());
+
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Diamonds));
+        assert!(!hand.is_flush());
+    }
+    #[test]
+    fn straight_flush(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Three, Suit::Spades));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Spades));
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Spades));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(hand.is_straight_flush());
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Three, Suit::Spades));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Hearts));
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Spades));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(!hand.is_straight_flush());
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Three, Suit::Spades));
+        hand.cards[2] = Some(Card::new(Rank::Ten, Suit::Spades));
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Spades));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(!hand.is_straight_flush());
+
+        hand.cards[2] = Some(Card::Joker);
+        assert!(hand.is_straight_flush());
+
+        hand.cards[0] = Some(Card::Joker);
+        assert!(hand.is_straight_flush());
+
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Hearts));
+        assert!(!hand.is_straight_flush());
+    }
+    #[test]
+    fn royal_flush(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Ten, Suit::Hearts));
+        hand.cards[1] = Some(Card::new(Rank::Jack, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Queen, Suit::Hearts));
+        hand.cards[3] = Some(Card::new(Rank::King, Suit::Hearts));
+        hand.cards[4] = Some(Card::new(Rank::Ace, Suit::Hearts));
+        assert!(hand.is_royal_flush());
+        assert!(hand.is_straight_flush());
+        assert_eq!(hand.evaluate(), Some(Prize::RoyalFlush));
+
+        // a joker makes it a wild royal
+        hand.cards[1] = Some(Card::Joker);
+        assert!(!hand.is_royal_flush());
+        assert!(hand.is_wild_royal_flush());
+        assert_eq!(hand.evaluate(), Some(Prize::WildRoyalFlush));
+
+        // the joker can also be the ace
+        hand.cards[1] = Some(Card::new(Rank::Jack, Suit::Hearts));
+        hand.cards[4] = Some(Card::Joker);
+        assert_eq!(hand.evaluate(), Some(Prize::WildRoyalFlush));
+
+        // king high is only a straight flush
+        hand.cards[4] = Some(Card::new(Rank::Nine, Suit::Hearts));
+        assert!(!hand.is_wild_royal_flush());
+        assert_eq!(hand.evaluate(), Some(Prize::StraightFlush));
+
+        // not a flush
+        hand.cards[4] = Some(Card::new(Rank::Ace, Suit::Spades));
+        assert!(!hand.is_wild_royal_flush());
+        assert_eq!(hand.evaluate(), Some(Prize::Straight));
+    }
+    #[test]
+    fn five_of_a_kind(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Nine, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Nine, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Nine, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Nine, Suit::Diamonds));
+        hand.cards[4] = Some(Card::Joker);
+
+        assert!(hand.is_five_of_a_kind());
+
+        hand.cards[3] = Some(Card::Joker);
+
+        assert!(hand.is_five_of_a_kind());
+
+        hand.cards[0] = Some(Card::new(Rank::Eight, Suit::Spades));
+
+        assert!(!hand.is_five_of_a_kind());
+        assert!(hand.is_four_of_a_kind());
+
+        // no five of a kind without jokers
+        hand.cards[0] = Some(Card::new(Rank::Nine, Suit::Spades));
+        hand.cards[3] = Some(Card::new(Rank::Nine, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Two, Suit::Spades));
+
+        assert!(!hand.is_five_of_a_kind());
+    }
+    #[test]
+    fn four_of_a_kind(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Two, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(hand.is_four_of_a_kind());
+
+        hand.cards[2] = Some(Card::new(Rank::Five, Suit::Clubs));
+
+        assert!(!hand.is_four_of_a_kind());
+
+        hand.cards[4] = Some(Card::Joker);
+        assert!(hand.is_four_of_a_kind());
+
+        hand.cards[3] = Some(Card::new(Rank::Seven, Suit::Diamonds));
+        assert!(!hand.is_four_of_a_kind());
+
+        hand.cards[2] = Some(Card::Joker);
+        assert!(hand.is_four_of_a_kind());
+    }
+    #[test]
+    fn three_of_a_kind(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Queen, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(hand.is_three_of_a_kind());
+
+        hand.cards[2] = Some(Card::new(Rank::Five, Suit::Clubs));
+
+        assert!(!hand.is_three_of_a_kind());
+
+        hand.cards[4] = Some(Card::Joker);
+        assert!(hand.is_three_of_a_kind());
+
+        hand.cards[0] = Some(Card::Joker);
+        hand.cards[1] = Some(Card::new(Rank::Four, Suit::Hearts));
+        assert!(hand.is_three_of_a_kind());
+    }
+    #[test]
+    fn full_house(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Four, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Four, Suit::Spades));
+
+        assert!(hand.is_full_house());
+
+        hand.cards[4] = Some(Card::new(Rank::Five, Suit::Clubs));
+
+        assert!(!hand.is_full_house());
+
+        // two pairs and a joker
+        hand.cards[0] = Some(Card::Joker);
+        hand.cards[4] = Some(Card::new(Rank::Four, Suit::Spades));
+        assert!(hand.is_full_house());
+
+        // pair and two jokers
+        hand.cards[1] = Some(Card::Joker);
+        assert!(hand.is_full_house());
+
+        // four of a kind is not a full house
+        hand.cards[0] = Some(Card::new(Rank::Four, Suit::Clubs));
+        hand.cards[1] = Some(Card::new(Rank::Four, Suit::Hearts));
+        hand.cards[2] = Some(Card::Joker);
+        assert!(!hand.is_full_house());
+
+        // pair, joker and two single cards
+        hand.cards[1] = Some(Card::new(Rank::Nine, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Five, Suit::Diamonds));
+        assert!(!hand.is_full_house());
+    }
+    #[test]
+    fn two_pairs(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Four, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Four, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+
+        assert!(hand.is_two_pairs());
+
+        hand.cards[0] = Some(Card::new(Rank::Seven, Suit::Clubs));
+
+        assert!(!hand.is_two_pairs());
+
+        // pair, joker and a single
+        hand.cards[0] = Some(Card::Joker);
+        assert!(hand.is_two_pairs());
+
+        // two jokers pair up with single cards
+        hand.cards[2] = Some(Card::new(Rank::Nine, Suit::Clubs));
+        hand.cards[3] = Some(Card::Joker);
+        assert!(hand.is_two_pairs());
+
+        hand.cards[0] = Some(Card::new(Rank::Seven, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Ten, Suit::Diamonds));
+        assert!(!hand.is_two_pairs());
+    }
+    #[test]
+    fn evaluate(){
+        let mut hand = Hand::new();
+
+        assert_eq!(hand.evaluate(), None);
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Four, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Four, Suit::Spades));
+
+        // full house is also three of a kind and two pairs
+        assert_eq!(hand.evaluate(), Some(Prize::FullHouse));
+
+        hand.cards[3] = Some(Card::Joker);
+        assert_eq!(hand.evaluate(), Some(Prize::FourOfAKind));
+
+        hand.cards[4] = Some(Card::Joker);
+        assert_eq!(hand.evaluate(), Some(Prize::FiveOfAKind));
+
+        hand.cards[0] = Some(Card::new(Rank::Three, Suit::Clubs));
+        hand.cards[1] = Some(Card::new(Rank::Four, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Clubs));
+        assert_eq!(hand.evaluate(), Some(Prize::StraightFlush));
+
+        hand.cards[4] = Some(Card::new(Rank::Queen, Suit::Clubs));
+        assert_eq!(hand.evaluate(), Some(Prize::Flush));
+
+        hand.cards[4] = Some(Card::new(Rank::Three, Suit::Hearts));
+        assert_eq!(hand.evaluate(), Some(Prize::ThreeOfAKind));
+
+        hand.cards[3] = Some(Card::new(Rank::Nine, Suit::Diamonds));
+        assert_eq!(hand.evaluate(), None);
+    }
+    #[test]
+    fn winning_cards(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Seven, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Two, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Four, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::Four, Suit::Spades));
+
+        assert_eq!(hand.winning_cards(), [true, false, true, true, true]);
+
+        hand.cards[4] = Some(Card::Joker);
+        assert_eq!(hand.winning_cards(), [true, false, true, false, true]);
+
+        hand.cards[0] = Some(Card::new(Rank::Jack, Suit::Spades));
+        hand.cards[3] = Some(Card::new(Rank::Two, Suit::Diamonds));
+        assert_eq!(hand.winning_cards(), [false, false, true, true, true]);
+
+        // the jokers go with the highest card
+        hand.cards[3] = Some(Card::Joker);
+        assert_eq!(hand.winning_cards(), [true, false, false, true, true]);
+
+        hand.cards[3] = Some(Card::new(Rank::Three, Suit::Clubs));
+        hand.cards[4] = Some(Card::new(Rank::Six, Suit::Spades));
+        assert_eq!(hand.winning_cards(), [false; 5]);
+    }
+    #[test]
+    fn combinations(){
+        let cards: Vec<Card> = Rank::ALL[..6].iter().map(|&rank| Card::new(rank, Suit::Spades)).collect();
+        let mut count = 0;
+        let mut last = Vec::new();
+        for_each_combination(&cards, 2, |combination|{
+            count += 1;
+            last = combination.to_vec();
+        });
+        assert_eq!(count, 15);
+        assert_eq!(last, vec![cards[4], cards[5]]);
+
+        count = 0;
+        for_each_combination(&cards, 0, |_| count += 1);
+        assert_eq!(count, 1);
+        for_each_combination(&cards, 7, |_| count += 1);
+        assert_eq!(count, 1);
+
+        assert_eq!(selection_to_mask(&mask_to_selection(0b10110)), 0b10110);
+    }
+    #[test]
+    fn hold_values(){
+        let mut hand = Hand::new();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Five, Suit::Clubs));
+        hand.cards[3] = Some(Card::new(Rank::Nine, Suit::Diamonds));
+        hand.cards[4] = Some(Card::new(Rank::King, Suit::Clubs));
+        let remaining = [
+            Card::new(Rank::Two, Suit::Clubs),
+            Card::new(Rank::Five, Suit::Spades),
+            Card::new(Rank::Seven, Suit::Hearts),
+            Card::new(Rank::Eight, Suit::Hearts),
+            Card::new(Rank::Ten, Suit::Diamonds),
+            Card::Joker,
+        ];
+        let values = hand.hold_values(&remaining, &Paytable::classic(), 20);
+
+        assert_eq!(values[0b11111], 0.0);
+        // three of the six cards make three of a kind or two pairs
+        assert_eq!(values[0b01111], 20.0);
+        // holding the pair, 4 of the 20 draws make four of a kind
+        // and 12 three of a kind
+        assert_eq!(values[0b00011], (4.0 * 300.0 + 12.0 * 40.0) / 20.0);
+    }
+}
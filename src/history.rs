@@ -0,0 +1,78 @@
+//! Hand history of the played rounds.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::card::Card;
+use crate::hand::Prize;
+
+/// One played round in the hand history file.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HandRecord{
+    /// Round the hand was played on
+    pub round: u32,
+    /// Which of the hands played at once, 0 for the dealt one
+    #[serde(default)]
+    pub hand: u32,
+    /// Bet of the hand
+    pub bet: u32,
+    /// The five cards dealt at the start of the round
+    pub initial: [Card; 5],
+    /// Slots held when drawing
+    pub held: [bool; 5],
+    /// Cards drawn to the slots that were not held, in slot order
+    pub replacements: Vec<Card>,
+    /// Prize of the final hand, if any
+    pub prize: Option<Prize>,
+    /// What the paytable paid, before any doubling
+    pub payout: u32,
+    /// Jackpot paid on top of the payout
+    #[serde(default)]
+    pub jackpot: u32,
+}
+impl HandRecord{
+    /// The hand after the draw. Needs a replacement for every card not
+    /// held, as read_all checks.
+    pub fn final_cards(&self)->[Card; 5]{
+        let mut cards = self.initial;
+        let mut replacements = self.replacements.iter();
+        for (card, held) in cards.iter_mut().zip(self.held){
+            if !held{
+                *card = *replacements.next().unwrap();
+            }
+        }
+        cards
+    }
+
+    /// Appends the record as one line to the history file.
+    pub fn append(&self, path: &Path)->Result<(), String>{
+        use std::io::Write;
+        if let Some(dir) = path.parent(){
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let line = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::OpenOptions::new().create(true).append(true).open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| format!("Can not write hand history {}: {}", path.display(), e))
+    }
+
+    /// Reads every record from a history file. Every card not held must
+    /// have a replacement.
+    pub fn read_all(path: &Path)->Result<Vec<HandRecord>, String>{
+        let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can not read hand history {}: {}", path.display(), e))?;
+        text.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)|{
+            let record: HandRecord = serde_json::from_str(line)
+            .map_err(|e| format!("Invalid hand history {} line {}: {}", path.display(), i + 1, e))?;
+            let drawn = record.held.iter().filter(|held| !**held).count();
+            if record.replacements.len() != drawn{
+                return Err(format!("Invalid hand history {} line {}: {} replacements for {} cards not held",
+                path.display(), i + 1, record.replacements.len(), drawn));
+            }
+            Ok(record)
+        })
+        .collect()
+    }
+}
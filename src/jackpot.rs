@@ -0,0 +1,93 @@
+//! Progressive jackpot pool shared by every game on the machine.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::persist::{load_json_or_default, save_json};
+
+/// Share of every bet the pool takes, when not configured otherwise.
+pub const DEFAULT_JACKPOT_PERCENT: u32 = 1;
+
+/// Pool fed by a percentage of every bet, paid out in full on the top
+/// prize at the maximum bet. Only the pool is saved, the percentage is a
+/// setting of the machine.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Jackpot{
+    /// Percentage of every bet added to the pool
+    #[serde(skip)]
+    pub percent: u32,
+    /// The pool in hundredths of a credit, so that small shares add up
+    pool_hundredths: u64,
+}
+impl Jackpot{
+    /// An empty pool taking the percentage of every bet.
+    pub fn new(percent: u32)->Self{
+        Self{
+            percent,
+            pool_hundredths: 0,
+        }
+    }
+
+    /// Whole credits in the pool.
+    pub fn pool(&self)->u32{
+        u32::try_from(self.pool_hundredths / 100).unwrap_or(u32::MAX)
+    }
+
+    /// Adds the share of the bet to the pool.
+    pub fn contribute(&mut self, bet: u32){
+        self.pool_hundredths += u64::from(bet) * u64::from(self.percent);
+    }
+
+    /// Empties the pool, returning the whole credits won. Fractions of a
+    /// credit stay for the next jackpot.
+    pub fn take(&mut self)->u32{
+        let amount = self.pool();
+        self.pool_hundredths -= u64::from(amount) * 100;
+        amount
+    }
+
+    /// Reads the pool saved with save, an empty pool if there is no file.
+    pub fn load(path: &Path, percent: u32)->Result<Self, String>{
+        let mut jackpot: Jackpot = load_json_or_default(path, "jackpot")?;
+        jackpot.percent = percent;
+        Ok(jackpot)
+    }
+
+    /// Writes the pool to a file.
+    pub fn save(&self, path: &Path)->Result<(), String>{
+        save_json(self, path, "jackpot")
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn jackpot_pool(){
+        let path = crate::persist::temp_path("jackpot.json");
+        let mut jackpot = Jackpot::load(&path, 1).unwrap();
+        assert_eq!(jackpot.pool(), 0);
+
+        // shares below a credit add up
+        for _bet in 0..7{
+            jackpot.contribute(20);
+        }
+        assert_eq!(jackpot.pool(), 1);
+
+        jackpot.save(&path).unwrap();
+        let mut loaded = Jackpot::load(&path, 5).unwrap();
+        assert_eq!(loaded.pool(), 1);
+        assert_eq!(loaded.percent, 5);
+        loaded.contribute(100);
+        assert_eq!(loaded.pool(), 6);
+
+        assert_eq!(loaded.take(), 6);
+        assert_eq!(loaded.pool(), 0);
+        // the fraction stays in the pool
+        loaded.contribute(60);
+        assert_eq!(loaded.pool(), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
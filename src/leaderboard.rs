@@ -0,0 +1,122 @@
+//! High scores of the finished games by player name.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::persist::{load_json_or_default, save_json};
+
+/// Most letters in a player name, enough for initials.
+pub const MAX_NAME_LEN: usize = 3;
+
+/// Most scores kept on the leaderboard.
+pub const MAX_SCORES: usize = 100;
+
+/// The result of a finished game, the best one of a player on the board.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Score{
+    /// Initials of the player
+    pub name: String,
+    /// Round the game ended on
+    pub rounds: u32,
+    /// Most credits on the meter during the game
+    pub peak_funds: u32,
+    /// Biggest payout of a single draw
+    pub biggest_payout: u32,
+}
+
+/// The best game of each player, the best first.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Leaderboard{
+    /// At most MAX_SCORES scores, the best first
+    pub scores: Vec<Score>,
+}
+impl Leaderboard{
+    /// Checks the name is one to MAX_NAME_LEN letters or digits and
+    /// returns it in upper case.
+    pub fn validate_name(name: &str)->Result<String, String>{
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN
+        || !name.chars().all(|c| c.is_alphanumeric()){
+            return Err(format!("Invalid name {}, use 1-{} letters or digits", name, MAX_NAME_LEN));
+        }
+        Ok(name.to_uppercase())
+    }
+
+    /// Adds the score of a game, replacing the player's earlier best if
+    /// it ranks higher. Returns its place from 1, or None if it did not
+    /// beat the player's best or fell below MAX_SCORES.
+    pub fn record(&mut self, score: Score)->Option<usize>{
+        // most rounds first, then most funds
+        let rank = |score: &Score| (score.rounds, score.peak_funds, score.biggest_payout);
+        if let Some(i) = self.scores.iter().position(|other| other.name == score.name){
+            if rank(&self.scores[i]) >= rank(&score){
+                return None;
+            }
+            self.scores.remove(i);
+        }
+        let place = self.scores.partition_point(|other| rank(other) >= rank(&score));
+        if place >= MAX_SCORES{
+            return None;
+        }
+        self.scores.insert(place, score);
+        self.scores.truncate(MAX_SCORES);
+        Some(place + 1)
+    }
+
+    /// Reads the leaderboard saved with save, empty if there is no file.
+    pub fn load(path: &Path)->Result<Self, String>{
+        load_json_or_default(path, "leaderboard")
+    }
+
+    /// Writes the leaderboard to a file.
+    pub fn save(&self, path: &Path)->Result<(), String>{
+        save_json(self, path, "leaderboard")
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    fn score(name: &str, rounds: u32, peak_funds: u32)->Score{
+        Score{
+            name: name.to_string(),
+            rounds,
+            peak_funds,
+            biggest_payout: 0,
+        }
+    }
+
+    #[test]
+    fn record_scores(){
+        let path = crate::persist::temp_path("leaderboard.json");
+        let mut leaderboard = Leaderboard::load(&path).unwrap();
+
+        assert_eq!(leaderboard.record(score("AAA", 10, 200)), Some(1));
+        assert_eq!(leaderboard.record(score("BBB", 25, 150)), Some(1));
+        // a tie on rounds goes by funds
+        assert_eq!(leaderboard.record(score("CCC", 10, 300)), Some(2));
+        // one row per player, a worse game does not replace the best
+        assert_eq!(leaderboard.record(score("BBB", 5, 400)), None);
+        assert_eq!(leaderboard.record(score("AAA", 10, 200)), None);
+        assert_eq!(leaderboard.record(score("AAA", 30, 100)), Some(1));
+        assert_eq!(leaderboard.scores, [score("AAA", 30, 100), score("BBB", 25, 150), score("CCC", 10, 300)]);
+
+        for i in 0..MAX_SCORES{
+            leaderboard.record(score(&i.to_string(), 50, 0));
+        }
+        assert_eq!(leaderboard.scores.len(), MAX_SCORES);
+        assert_eq!(leaderboard.record(score("EEE", 1, 0)), None);
+        assert_eq!(leaderboard.scores.len(), MAX_SCORES);
+
+        leaderboard.save(&path).unwrap();
+        assert_eq!(Leaderboard::load(&path).unwrap(), leaderboard);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(Leaderboard::validate_name(" ab1 "), Ok("AB1".to_string()));
+        assert!(Leaderboard::validate_name("").is_err());
+        assert!(Leaderboard::validate_name("ABCD").is_err());
+        assert!(Leaderboard::validate_name("A.B").is_err());
+    }
+}
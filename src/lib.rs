@@ -0,0 +1,48 @@
+//! Jokeri Pokeri, the Finnish five card draw poker with two wild jokers.
+//!
+//! The library has the cards, the deck, hand evaluation and the game's
+//! state machine, with no input or output of its own. The terminal game
+//! in the binary is one front-end built on it.
+//!
+//! ```
+//! use rust_jokeri_pokeri::{GameError, JokeriPokeri, Paytable};
+//!
+//! let mut game = JokeriPokeri::new(Paytable::classic(), 42);
+//! game.start_round(20)?;
+//! game.toggle_hold(0)?;
+//! game.draw()?;
+//! game.end_round()?;
+//! assert_eq!(game.round(), 2);
+//! # Ok::<(), GameError>(())
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod card;
+pub mod deck;
+pub mod game;
+pub mod hand;
+pub mod history;
+pub mod jackpot;
+pub mod leaderboard;
+pub mod money;
+pub mod paytable;
+mod persist;
+pub mod rtp;
+pub mod rules;
+pub mod simulate;
+pub mod stats;
+
+pub use card::{Card, Rank, Suit};
+pub use deck::Deck;
+pub use game::{DoubleUpGuess, GameError, GameEvent, GameResult, GameState, JokeriPokeri};
+pub use hand::{Hand, Prize};
+pub use history::HandRecord;
+pub use jackpot::Jackpot;
+pub use leaderboard::{Leaderboard, Score};
+pub use money::{format_euros, Denomination};
+pub use paytable::{Paytable, PaytableRow};
+pub use rtp::{calculate_rtp, RtpReport};
+pub use rules::Rules;
+pub use simulate::{simulate, strategy_by_name, SimulationReport, Strategy};
+pub use stats::Stats;
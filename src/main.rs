@@ -1,457 +1,206 @@
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::char;
 use console::Term;
+use rust_jokeri_pokeri::game::MAX_HANDS;
+use rust_jokeri_pokeri::hand::{mask_to_selection, selection_to_mask};
+use rust_jokeri_pokeri::jackpot::DEFAULT_JACKPOT_PERCENT;
+use rust_jokeri_pokeri::{calculate_rtp, simulate, strategy_by_name};
+use rust_jokeri_pokeri::leaderboard::MAX_NAME_LEN;
+use rust_jokeri_pokeri::money::parse_euros;
+use rust_jokeri_pokeri::{format_euros, Denomination, Leaderboard, Rules, Score, Stats};
+use rust_jokeri_pokeri::{Card, Deck, DoubleUpGuess, GameEvent, GameState, Hand, HandRecord, Jackpot, JokeriPokeri, Paytable, Prize};
+use std::path::{Path, PathBuf};
 //use console::style;
 
+mod config;
+mod tui;
 
+use config::Config;
 
-struct Card {
-    value: u8,
-    suit: char,
-}
-impl Card{
-    fn new(value: u8, suit: char)->Card{
-        Card{
-            value: value,
-            suit: suit,
-        }
+
+
+/// Hand counts the terminal cycles through in the multi-hand mode.
+const HAND_COUNTS: [usize; 4] = [1, 3, 5, 10];
+
+/// Rows of the statistics shown as the summary of a session.
+const SUMMARY_ROWS: usize = 6;
+
+/// Short name of a card with its suit symbol, like "10♠".
+fn card_label(card: &Card)->String{
+    match card{
+        Card::Natural{ rank, suit } => format!("{}{}", rank, suit.symbol()),
+        Card::Joker => "JK".to_string(),
     }
-    // pub fn print(&self){
-    //     println!("Value: {} Suit: {}", self.value, self.suit);
-    // }
 }
 
-struct Deck {
-    cards: Vec<Card>,
-    rng: rand::rngs::ThreadRng
-}
-impl Deck{
-    /// Creates a filled and shuffled Deck.
-    fn new()->Self{
-        let mut deck: Deck = Self { 
-            cards: Vec::new(),
-            rng: thread_rng(),
-        };
-        deck.fill_deck();
-        //deck.print();
-        deck.shuffle_deck();
-        return deck;
-    }
-
-    // fn print(&mut self){
-    //     for i in &self.cards{
-    //         println!("{} {}", i.value, i.suit);
-    //     }
-    // }
-    fn shuffle_deck(&mut self){
-        self.cards.shuffle(&mut self.rng);
-    }
-    fn fill_deck(&mut self){
-        // add base cards
-        // values
-        for value in 1..=13{
-            //suites
-            for character_code in 0x2660..=0x2663{
-                self.cards.push(Card::new(u8::from(value)
-                , char::from_u32(character_code).unwrap()))
-            }
-        }
-        // TODO: add 2 joker cards (and implement)
-        // self.cards.push(Card::new(0, 'J'));
-        // self.cards.push(Card::new(0, 'J'));
-    }
+/// Most players shown on the leaderboard.
+const LEADERBOARD_ROWS: usize = 10;
+
+/// Screens shown over the game.
+enum Overlay{
+    Stats,
+    Leaderboard,
+    /// Name for the leaderboard being typed after game over
+    NameEntry(String),
 }
 
-struct Hand{
-    //cards: Vec<Card>,
-    cards: [Option<Card>; 5],
-    //selections: Vec<u8>,
+/// Terminal front-end of the game.
+struct TerminalUi{
+    game: JokeriPokeri,
+    selector: usize,
+    /// File every played round is appended to, if any.
+    history_path: Option<PathBuf>,
+    /// Expected payouts of the hold choices, when asked for a hint.
+    hold_values: Option<[f64; 32]>,
+    /// Result of the latest action, shown until the next one.
+    message: Option<String>,
+    /// Statistics since the program started
+    session: Stats,
+    /// Statistics of every session, saved between them
+    lifetime: Stats,
+    leaderboard: Leaderboard,
+    /// File the leaderboard is saved to after every entry, if any.
+    leaderboard_path: Option<PathBuf>,
+    /// Money put in with the cash in key, in cents
+    cash_in_cents: u64,
+    overlay: Option<Overlay>,
 }
-impl Hand{
-    pub fn new()->Self{
-        Self { 
-            cards: [None, None, None, None, None],
-            //selections: Vec::new(),
-        }
-    }
-
-    /// Returns true, if any of the card slots are None
-    // fn has_nones(&self)->bool{
-    //     for element in &self.cards{
-    //         match element{
-    //             Some(_)=>{ continue; }
-    //             None=>{
-    //                 return true
-    //             }
-    //         }
-    //     }
-    //     return false
-    // }
-
-    fn is_straight_flush(&self)->bool{
-        if self.is_straight() && self.is_flush(){
-            return true;
+impl TerminalUi{
+    fn new(game: JokeriPokeri, history_path: Option<PathBuf>, cash_in_cents: u64)->Self{
+        Self{
+            game,
+            selector: 0,
+            history_path,
+            hold_values: None,
+            message: None,
+            session: Stats::default(),
+            lifetime: Stats::default(),
+            leaderboard: Leaderboard::default(),
+            leaderboard_path: None,
+            cash_in_cents,
+            overlay: None,
         }
-        return false;
     }
 
-    fn is_four_of_a_kind(&self)->bool{
-        // loop through cards
-        for i in &self.cards{
-            match i{
-                Some(card)=>{
-                    // check how many times that cards value is found
-                    let count = self.cards.iter()
-                    .filter(|&x| x.as_ref().unwrap().value == card.value).count();
-                    if count >= 4{
-                        return true;
-                    }
-                }
-                None=>{
-                    return false;
-                }
-            }
+    /// Lines of the leaderboard screen, the header first.
+    fn leaderboard_lines(&self)->Vec<String>{
+        let mut lines = vec![format!("{:>3}  {:<6}{:>10}{:>14}{:>16}", "", "Name", "Rounds",
+        "Peak funds", "Biggest payout")];
+        for (i, score) in self.leaderboard.scores.iter().take(LEADERBOARD_ROWS).enumerate(){
+            lines.push(format!("{:>3}. {:<6}{:>10}{:>14}{:>16}", i + 1, score.name, score.rounds,
+            score.peak_funds, score.biggest_payout));
         }
-        return false;
-    }
-
-    fn is_full_house(&self)->bool{
-        if self.is_three_of_a_kind(){
-            // check that there are only two kinds of values in hand
-            // (four of a kind is checked before full house)
-            let mut unique_values = Vec::<u8>::new();
-            for element in &self.cards{
-                match element{
-                    Some(card)=>{
-                        if !unique_values.contains(&card.value){
-                            unique_values.push(card.value);
-                        }
-                    }
-                    None=>{
-                        return false;
-                    }
-                }
-                if unique_values.len() > 2{
-                    return false;
-                }
-            }
-            return true;
+        if self.leaderboard.scores.is_empty(){
+            lines.push("No scores yet".to_string());
         }
-        return false;
+        lines
     }
 
-    fn is_three_of_a_kind(&self)->bool{
-        // loop through cards
-        for i in &self.cards{
-            match i{
-                Some(card)=>{
-                    // check how many times that cards value is found
-                    let count = self.cards.iter()
-                    .filter(|&x| x.as_ref().unwrap().value == card.value).count();
-                    if count >= 3{
-                        return true;
-                    }
-                }
-                None=>{
-                    return false;
-                }
+    /// Puts the finished game on the leaderboard under the name.
+    fn enter_score(&mut self, name: &str){
+        let name = match Leaderboard::validate_name(name){
+            Ok(name)=>{ name }
+            Err(e)=>{
+                self.message = Some(e);
+                return;
+            }
+        };
+        let place = self.leaderboard.record(Score{
+            name: name.clone(),
+            rounds: self.game.round(),
+            peak_funds: self.game.peak_funds(),
+            biggest_payout: self.game.biggest_payout(),
+        });
+        self.message = Some(match place{
+            Some(place)=>{ format!("{} is number {} on the leaderboard.", name, place) }
+            None=>{ format!("{} did not place on the leaderboard.", name) }
+        });
+        if let Some(path) = &self.leaderboard_path{
+            if let Err(e) = self.leaderboard.save(path){
+                self.message = Some(e);
             }
         }
-        return false;
+        self.overlay = None;
     }
 
-    fn is_flush(&self)->bool{
-        let first_suit = match &self.cards[0]{
-            Some(card)=>{
-                card.suit
-            }
-            None=>{
-                return false;
-            }
+    /// Rows of the statistics screen, the name with the session and the
+    /// lifetime values. The prize hits come after the first SUMMARY_ROWS.
+    fn stats_rows(&self)->Vec<(&'static str, String, String)>{
+        let return_rate = |stats: &Stats|{
+            stats.return_rate().map_or("-".to_string(), |rate| format!("{:.1} %", rate * 100.0))
         };
-        for i in 1..self.cards.len(){
-            let _ = match &self.cards[i]{
-                Some(card)=>{
-                    if first_suit == card.suit{
-                        continue;
-                    }
-                    else {
-                        return false;
-                    }
-                }
-                None=>{
-                    return false;
-                }
-            };
+        let mut rows = vec![
+            ("Hands", self.session.hands.to_string(), self.lifetime.hands.to_string()),
+            ("Wagered", self.session.wagered.to_string(), self.lifetime.wagered.to_string()),
+            ("Won", self.session.won.to_string(), self.lifetime.won.to_string()),
+            ("Return", return_rate(&self.session), return_rate(&self.lifetime)),
+            ("Biggest win", self.session.biggest_win.to_string(), self.lifetime.biggest_win.to_string()),
+            ("Longest losing streak", self.session.longest_losing_streak.to_string(),
+            self.lifetime.longest_losing_streak.to_string()),
+        ];
+        for prize in Prize::ALL{
+            rows.push((prize.as_str(), self.session.hits(prize).to_string(),
+            self.lifetime.hits(prize).to_string()));
         }
-        return true;
+        rows
     }
 
-    fn is_straight(&self)->bool{
-        let mut hand_vec  = Vec::<&Card>::new();
-        for element in &self.cards{
-            match element{
-                Some(card)=>{
-                    hand_vec.push(card);
-                }
-                None=>{
-                    // Not enough cards in hand for a straight
-                    return false;
-                }
-            }
-        }
-        hand_vec.sort_unstable_by_key(|card| card.value);
-        let mut curr_value: u8 = hand_vec.get(0).unwrap().value;
-        for i in 1..hand_vec.len(){
-            let next_value = hand_vec.get(i).unwrap().value;
-            if next_value - curr_value != 1{
-                return false;
-            }
-            else {
-                curr_value = next_value;
+    fn print_statistics(&self){
+        println!("{:<25}{:>12}{:>12}", "Statistics", "Session", "Lifetime");
+        for (i, (name, session, lifetime)) in self.stats_rows().into_iter().enumerate(){
+            if i == SUMMARY_ROWS{
+                println!();
             }
+            println!("{:<25}{:>12}{:>12}", name, session, lifetime);
         }
-        return true;
     }
 
-    fn is_two_pairs(&self)->bool{
-        let mut pairs_count: u8 = 0;
-        let mut first_pair_value: u8 = 0;
-        for i in &self.cards{
-            match i{
-                Some(card)=>{
-                    // check how many times that cards value is found
-                    let count = self.cards.iter()
-                    .filter(|&x| x.as_ref().unwrap().value == card.value).count();
-                    if count >= 2 && card.value != first_pair_value{
-                        pairs_count += 1;
-                        first_pair_value = card.value;
+    /// Reacts to what happened in the game.
+    fn handle_events(&mut self, events: Vec<GameEvent>){
+        for event in events{
+            match event{
+                GameEvent::Drawn(record) =>{
+                    self.hold_values = None;
+                    self.session.record(&record);
+                    self.lifetime.record(&record);
+                    if let Some(path) = &self.history_path{
+                        if let Err(e) = record.append(path){
+                            self.message = Some(e);
+                        }
                     }
                 }
-                None=>{
-                    return false;
+                GameEvent::DoubleUpResolved{ won: true, .. } =>{
+                    self.message = Some("Right guess!".to_string());
                 }
-            }
-            if pairs_count >= 2{
-                return true;
-            }
-        }
-        return false;
-    }
-
-    
-
-    fn print(&self){
-        // suits row
-        for element in &self.cards{
-            match element{
-                Some(card) =>{
-                    print!(" {:<2}", card.suit);
+                GameEvent::DoubleUpResolved{ won: false, .. } =>{
+                    self.message = Some("Wrong guess.".to_string());
                 }
-                None =>{
-                    print!(" _ ");
+                GameEvent::Collected{ amount, .. } =>{
+                    self.message = Some(format!("Collected {}.", amount));
                 }
-            }
-        }
-        println!();
-        // values row
-        for element in &self.cards{
-            match element{
-                Some(card) =>{
-                    print!(" {:<2}", card.value);
+                GameEvent::GameOver{ .. } =>{
+                    self.overlay = Some(Overlay::NameEntry(String::new()));
                 }
-                None =>{
-                    print!(" _ ");
+                GameEvent::JackpotWon{ amount, .. } =>{
+                    self.message = Some(format!("JACKPOT! {} added to funds.", amount));
                 }
-            }
-        }
-        println!();
-    }
-    // fn is_wincondition1...
-    // fn is_wincondition2...
-}
-
-#[derive(PartialEq)]
-enum GameState{
-    Betting,
-    HandSelection,
-    PayOut,
-    GameOver,
-}
-
-enum Prize{
-    StraightFlush,
-    FourOfAKind,
-    FullHouse,
-    Flush,
-    Straight,
-    ThreeOfAKind,
-    TwoPairs,
-}
-impl Prize{
-    fn as_str(&self)->&'static str{
-        match self{
-            Prize::StraightFlush => "Straight flush",
-            Prize::FourOfAKind => "Four-of-a-kind",
-            Prize::FullHouse => "Full house",
-            Prize::Flush => "Flush",
-            Prize::Straight => "Straight",
-            Prize::ThreeOfAKind => "Three-of-a-kind",
-            Prize::TwoPairs => "Two pairs",
-        }
-    }
-}
-/// Struct to hold all of the game's data and functionality
-struct JokeriPokeri{
-    deck: Deck,
-    hand: Hand,
-    discarded: Vec<Card>,
-    funds: u32,
-    round: u32,
-    bet_amount: u32,
-    latest_prize: Option<Prize>,
-    latest_payout: u32,
-    state: GameState,
-    playing: bool,
-    selector: usize,
-    selected: [bool; 5],
-}
-impl JokeriPokeri{
-    fn new()->Self{
-        let game: JokeriPokeri = JokeriPokeri { 
-            deck: Deck::new(), 
-            hand: Hand::new(),
-            discarded: Vec::new(),
-            funds: 100, 
-            round: 1, 
-            bet_amount: 20,
-            latest_payout: 0,
-            state: GameState::Betting,
-            playing: true, 
-            selector: 0,
-            selected: [false, false, false, false, false],
-            latest_prize: None,
-        };
-        return game;
-    }
-
-    fn reset_game(&mut self){
-        self.deck = Deck::new();
-        self.hand = Hand::new();
-        self.discarded = Vec::new();
-        self.funds = 100;
-        self.round = 1;
-        self.bet_amount = 20;
-        self.latest_payout = 0;
-        self.state = GameState::Betting;
-        self.playing = true;
-        self.selector = 0;
-        self.selected = [false, false, false, false, false];
-        self.latest_prize = None;
-    }
-
-    /// Deals cards from deck to every unselected slot in hand.
-    /// Discards all unselected cards if in hand.
-    fn deal(&mut self){
-        for i in 0..self.hand.cards.len(){
-            // skip those cards that are selected to hold
-            if !self.selected[i]{
-                // discard if unselected card in hand
-                let element = self.hand.cards[i].take();
-                match element{
-                    Some(card) =>{
-                        self.discarded.push(card);
-                    }
-                    _ =>{
-
-                    }
-                }
-                // draw a new card to hand.
-                self.hand.cards[i] = Some(self.deck.cards.remove(0));
-            }
-        }
-    }
-
-    fn reset_deck_and_hand(&mut self){
-        // discard hand to discard pile
-        for i in 0.. self.hand.cards.len(){
-            let element = self.hand.cards[i].take();
-            match element{
-                Some(card) => {
-                    self.discarded.push(card);
+                GameEvent::CashedIn{ credits, .. } =>{
+                    self.message = Some(format!("Cashed in {}, {} credits.",
+                    format_euros(self.game.denomination().value(credits)), credits));
                 }
-                None => {
+                GameEvent::CashedOut{ credits, cents } =>{
+                    self.message = Some(format!("Cashed out {} credits, {}.", credits, format_euros(cents)));
                 }
+                _ =>{}
             }
         }
-
-        // discard discard back to deck
-        for _i in 0..self.discarded.len(){
-            self.deck.cards.push(self.discarded.remove(0));
-        }
-        // shuffle deck
-        self.deck.shuffle_deck();
-    }
-
-    fn toggle_selection(&mut self){
-        self.selected[self.selector] = !self.selected[self.selector];
-    }
-
-    fn reset_selections(&mut self){
-        for i in 0..self.selected.len(){
-            self.selected[i] = false;
-        }
     }
 
-    fn cycle_bet_amount(&mut self){
-        if self.bet_amount < 100 && self.funds >= self.bet_amount + 20{
-            self.bet_amount += 20;
-        }
-        else {
-            self.bet_amount = 20;
-        }
-    }
-
-    // fn query_quit(&mut self, input: &String){
-    //     let input_lowercase = input.to_lowercase();
-    //     match input_lowercase.as_str(){
-    //         "quit" | "exit" =>{
-    //             println!("Quitting.");
-    //             self.playing = false;
-    //         }
-    //         _ => {
-
-    //         }
-    //     }
-    // }
-
-    // fn bet_money(&mut self, bet_amount: u32){
-    //     if bet_amount >= 1 && bet_amount <= 100
-    //     && self.funds >= bet_amount{
-    //         self.bet_amount = bet_amount;
-    //         self.funds -= bet_amount;
-    //         self.state = GameState::HandSelection;
-    //     } else{
-    //         println!("Invaid bet!")
-    //     }
-    // }
-
     fn print_hand_and_selector(&self){
-        self.hand.print();
+        print_cards(&self.game.hand().cards);
         // print hand selector row
-        if self.state == GameState::HandSelection{
-            // print selected row
-            for i in self.selected{
-                if i{
-                    print!("HLD");
-                } else {
-                    print!("   ");
-                }
-            }
-            println!();
+        if self.game.state() == GameState::HandSelection{
+            print_marks(self.game.selected(), "HLD");
             // print selector
-            for i in 0..self.hand.cards.len(){
+            for i in 0..self.game.hand().cards.len(){
                 if self.selector == i{
                     print!(" ^ ");
                 } else{
@@ -459,6 +208,19 @@ impl JokeriPokeri{
                 }
             }
             println!();
+            // print the best hold, if hint was asked
+            if let Some(values) = &self.hold_values{
+                let best = (0..values.len())
+                .max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap();
+                print_marks(mask_to_selection(best), "BST");
+                println!("Best hold pays {:.2}, your hold pays {:.2} on average",
+                values[best], values[selection_to_mask(&self.game.selected())]);
+            }
+        }
+        else if self.game.state() == GameState::PayOut || self.game.state() == GameState::DoubleUp{
+            // mark the cards that made the prize
+            print_marks(self.game.hand().winning_cards(), "WIN");
+            println!();
         }
         else {
             println!();
@@ -467,37 +229,91 @@ impl JokeriPokeri{
     }
 
     fn print_prizes(&self){
-        println!("{:<25}{:<10}", Prize::StraightFlush.as_str(), 40*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::FourOfAKind.as_str(), 15*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::FullHouse.as_str(), 7*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::Flush.as_str(), 4*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::Straight.as_str(), 3*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::ThreeOfAKind.as_str(), 2*self.bet_amount);
-        println!("{:<25}{:<10}", Prize::TwoPairs.as_str(), 2*self.bet_amount);
+        for prize in Prize::ALL{
+            if self.game.paytable().row(prize).is_some(){
+                println!("{:<25}{:<10}", prize.as_str(),
+                self.game.paytable().payout_wide(prize, self.game.bet_amount()));
+            }
+        }
+    }
+
+    /// Prints the extra hands of the multi-hand mode one row each.
+    fn print_extra_hands(&self){
+        if self.game.extra_hands().is_empty(){
+            return;
+        }
+        if self.game.state() != GameState::PayOut && self.game.state() != GameState::DoubleUp{
+            println!("Held cards are played on {} hands", self.game.hands());
+            return;
+        }
+        for (i, hand) in self.game.extra_hands().iter().enumerate(){
+            print!("{:>3}: ", i + 2);
+            for element in &hand.cards{
+                match element{
+                    Some(card) =>{ print!("{:<4}", card_label(card)); }
+                    None =>{ print!("{:<4}", '_'); }
+                }
+            }
+            match hand.evaluate(){
+                Some(prize)=>{
+                    println!(" {} {}", prize.as_str(),
+                    self.game.paytable().payout_wide(prize, self.game.bet_amount()));
+                }
+                None=>{ println!(); }
+            }
+        }
+        println!();
     }
 
     fn print_screen(&self){
+        match &self.overlay{
+            Some(Overlay::Stats)=>{
+                self.print_statistics();
+                println!();
+                println!("any key - back");
+                return;
+            }
+            Some(Overlay::Leaderboard)=>{
+                println!("Leaderboard");
+                for line in self.leaderboard_lines(){
+                    println!("{}", line);
+                }
+                println!();
+                println!("any key - back");
+                return;
+            }
+            _=>{}
+        }
         self.print_prizes();
         println!();
         self.print_hand_and_selector();
+        self.print_extra_hands();
         self.print_stats();
-        println!();
-        match self.state{
+        match &self.message{
+            Some(message)=>{ println!("{}", message); }
+            None=>{ println!(); }
+        }
+        match self.game.state(){
             GameState::Betting=>{
-                //self.hand.print();
+                //self.print_hand();
                 println!("b - cycle bet amount");
+                println!("m - cycle number of hands");
+                println!("c - cash in {}", format_euros(self.cash_in_cents));
+                println!("o - cash out");
+                println!("t - statistics");
+                println!("l - leaderboard");
                 println!("enter - start game");
             }
             GameState::HandSelection=>{
 
-                //self.hand.print();
+                //self.print_hand();
                 
                 //std::io::stdout().flush();
 
                 println!();
 
                 // print selector cursor row position
-                // for i in 0..self.hand.cards.len(){
+                // for i in 0..self.game.hand().cards.len(){
                 //     if self.selector == i{
                 //         print!(" ^ ");
                 //     } else{
@@ -506,30 +322,198 @@ impl JokeriPokeri{
                 // }
                 println!("left/right - move selector");
                 println!("space - select card");
+                println!("h - hint best hold");
                 println!("enter - continue");
 
             }
+            GameState::DoubleUp=>{
+                self.print_double_up_card();
+                println!("s - guess small (A-6)");
+                println!("l - guess large (8-K)");
+                println!("enter - back");
+            }
             GameState::PayOut=>{
-                match &self.latest_prize{
+                self.print_double_up_card();
+                match self.game.latest_prize(){
+                    _ if self.game.hands() > 1 && self.game.latest_payout() > 0 =>{
+                        println!("The hands won {}.", self.game.latest_payout());
+                    }
                     Some(prize) => {
-                        println!("{}!", prize.as_str());
+                        println!("{}! You won {}.", prize.as_str(), self.game.latest_payout());
                     }
                     _=>{println!("No win.");}
                 }
 
-                if self.funds != 0 {
+                if self.game.pending_win() > 0 {
+                    if self.game.can_double_up(){
+                        println!("d - double up");
+                    }
+                    println!("h - collect half");
+                    println!("enter - collect and new round");
+                }
+                else if self.game.funds() != 0 {
                     println!("enter - new round");
                 }
                 //self.print_deck_and_selector();
             }
             GameState::GameOver =>{
-                println!("OUT OF FUNDS");
-                println!("You made it round {}", self.round);
+                println!("GAME OVER");
+                println!("You made it round {}", self.game.round());
+                println!("Seed: {}", self.game.seed());
+                println!();
+                println!("This session:");
+                for (name, session, _) in self.stats_rows().into_iter().take(SUMMARY_ROWS){
+                    println!("{:<25}{:>12}", name, session);
+                }
+                println!();
+                if let Some(Overlay::NameEntry(name)) = &self.overlay{
+                    println!("Your initials for the leaderboard: {}_", name);
+                    println!("enter - save");
+                    println!("escape - skip");
+                    return;
+                }
+                println!("c - cash in {} and play on", format_euros(self.cash_in_cents));
                 println!("New game y/n?");
             }
         }
     }
 
+    fn print_double_up_card(&self){
+        match self.game.double_up_card(){
+            Some(Card::Natural{ rank, suit }) =>{
+                println!("Double up card: {} {}", suit.symbol(), rank);
+            }
+            Some(Card::Joker) =>{
+                println!("Double up card: Joker");
+            }
+            None =>{}
+        }
+    }
+
+    /// Applies a key press to the game. Returns false, when the player quits.
+    fn handle_key(&mut self, key: console::Key)->bool{
+        self.message = None;
+        if let Some(Overlay::NameEntry(name)) = &mut self.overlay{
+            match key{
+                console::Key::Char(c) if c.is_alphanumeric() && name.chars().count() < MAX_NAME_LEN =>{
+                    name.extend(c.to_uppercase());
+                }
+                console::Key::Backspace =>{
+                    name.pop();
+                }
+                console::Key::Enter =>{
+                    let name = name.clone();
+                    self.enter_score(&name);
+                }
+                console::Key::Escape =>{
+                    self.overlay = None;
+                }
+                _ =>{}
+            }
+            return true;
+        }
+        if self.overlay.take().is_some(){
+            // any key closes the statistics and the leaderboard
+            return true;
+        }
+        let state = self.game.state();
+        let events = match key{
+            console::Key::Escape =>{
+                return false;
+            }
+            console::Key::Enter =>{
+                match state{
+                    GameState::Betting =>{
+                        self.game.start_round(self.game.bet_amount())
+                    }
+                    GameState::HandSelection =>{
+                        self.game.draw()
+                    }
+                    GameState::DoubleUp =>{
+                        // back without guessing
+                        self.game.cancel_double_up()
+                    }
+                    GameState::PayOut =>{
+                        self.game.end_round()
+                    }
+                    _ =>{
+                        Ok(Vec::new())
+                    }
+                }
+            }
+            console::Key::Char('b') if state == GameState::Betting =>{
+                self.game.cycle_bet_amount()
+            }
+            console::Key::Char('m') if state == GameState::Betting =>{
+                // next of the usual counts, back to one after the last
+                let hands = HAND_COUNTS.into_iter().find(|&hands| hands > self.game.hands())
+                .unwrap_or(1);
+                self.game.set_hands(hands)
+            }
+            console::Key::Char('c') if state == GameState::Betting || state == GameState::GameOver =>{
+                self.game.cash_in(self.game.denomination().credits(self.cash_in_cents))
+            }
+            console::Key::Char('o') if state == GameState::Betting =>{
+                self.game.cash_out()
+            }
+            console::Key::Char('t') if state == GameState::Betting =>{
+                self.overlay = Some(Overlay::Stats);
+                Ok(Vec::new())
+            }
+            console::Key::Char('l') if state == GameState::Betting =>{
+                self.overlay = Some(Overlay::Leaderboard);
+                Ok(Vec::new())
+            }
+            console::Key::Char('d') if state == GameState::PayOut =>{
+                self.game.start_double_up()
+            }
+            console::Key::Char('h') if state == GameState::PayOut =>{
+                self.game.collect_half()
+            }
+            console::Key::Char('s') if state == GameState::DoubleUp =>{
+                self.game.double_up(DoubleUpGuess::Small)
+            }
+            console::Key::Char('l') if state == GameState::DoubleUp =>{
+                self.game.double_up(DoubleUpGuess::Large)
+            }
+            console::Key::Char('h') if state == GameState::HandSelection
+            && self.hold_values.is_none() =>{
+                self.game.hold_values().map(|values|{
+                    self.hold_values = Some(values);
+                    Vec::new()
+                })
+            }
+            console::Key::Char(' ') if state == GameState::HandSelection =>{
+                self.game.toggle_hold(self.selector)
+            }
+            console::Key::ArrowLeft if state == GameState::HandSelection
+            && self.selector > 0 =>{
+                // move selector left
+                self.selector -= 1;
+                Ok(Vec::new())
+            }
+            console::Key::ArrowRight if state == GameState::HandSelection
+            && self.selector < 4 =>{
+                // move selector right
+                self.selector += 1;
+                Ok(Vec::new())
+            }
+            console::Key::Char('y') if state == GameState::GameOver =>{
+                self.selector = 0;
+                self.game.reset_game()
+            }
+            console::Key::Char('n') if state == GameState::GameOver =>{
+                return false;
+            }
+            _ => { Ok(Vec::new()) }
+        };
+        match events{
+            Ok(events) =>{ self.handle_events(events); }
+            Err(e) =>{ self.message = Some(e.to_string()); }
+        }
+        true
+    }
+
     pub fn play(&mut self){
 
         //let mut playing: bool = true;
@@ -543,343 +527,538 @@ impl JokeriPokeri{
             self.print_screen();
 
             // handle input
-            match term.read_key().unwrap(){
-                console::Key::Escape =>{
-                    println!("Exiting...");
-                    break;
-                }
-                console::Key::Enter =>{
-                    match self.state{
-                        GameState::Betting =>{
-                            self.funds -= self.bet_amount;
-
-                            self.deck.shuffle_deck();
-                            self.reset_selections();
-                            self.deal();
-                            self.state = GameState::HandSelection;
-                        }
-                        GameState::HandSelection =>{
-                            //self.hand.print();
-                            self.deal();
-                            self.state = GameState::PayOut;
-                            // check wins
-                            if self.hand.is_straight_flush(){
-                                self.latest_prize = Some(Prize::StraightFlush);
-                                //println!("Straight flush!");
-                                self.funds += 40 * self.bet_amount;
-                            }
-                            else if self.hand.is_four_of_a_kind(){
-                                self.latest_prize = Some(Prize::FourOfAKind);
-                                //println!("Four of a kind!");
-                                self.funds += 15 * self.bet_amount;
-                            }
-                            else if self.hand.is_full_house(){
-                                self.latest_prize = Some(Prize::FullHouse);
-                                //println!("Full house!");
-                                self.funds += 7 * self.bet_amount;
-                            }
-                            else if self.hand.is_flush(){
-                                self.latest_prize = Some(Prize::Flush);
-                                //println!("Flush!");
-                                self.funds += 4 * self.bet_amount;
-                            }
-                            else if self.hand.is_straight(){
-                                self.latest_prize = Some(Prize::Straight);
-                                //println!("Straight!");
-                                self.funds += 3 * self.bet_amount;
-                            }
-                            else if self.hand.is_three_of_a_kind(){
-                                self.latest_prize = Some(Prize::ThreeOfAKind);
-                                //println!("Three of a kind!");
-                                self.funds += 2 * self.bet_amount;
-                            }
-                            else if self.hand.is_two_pairs(){
-                                self.latest_prize = Some(Prize::TwoPairs);
-                                //println!("Two pairs!");
-                                self.funds += 2 * self.bet_amount;
-                            }
-                            else{
-                                println!("No win");
-                            }
-                        }
-                        GameState::PayOut =>{
-                            
-                            if self.funds == 0{
-                                self.state = GameState::GameOver;
-                            }
-                            else{
-                                self.state = GameState::Betting;
-                                self.latest_prize = None;
-                                self.round += 1;
-                                self.reset_deck_and_hand();
-                            }
-                        }
-                        _ =>{
-                            
-                        }
-                    }
-                }
-                console::Key::Char('b') =>{
-                    if self.state == GameState::Betting{
-                        self.cycle_bet_amount();
-                    }
-                }
-                console::Key::Char(' ') =>{
-                    if self.state == GameState::HandSelection{
-                        self.toggle_selection();
-                    }
-                }
-                console::Key::ArrowLeft =>{
-                    if self.state == GameState::HandSelection{
-                        // move selector left
-                        if self.selector > 0{
-                            self.selector -= 1;
-                        }
-                    }
-                }
-                console::Key::ArrowRight =>{
-                    if self.state == GameState::HandSelection{
-                        // move selector right
-                        if self.selector < 4{
-                            self.selector += 1;
-                        }
-                    }
-                }
-                console::Key::Char('y') =>{
-                    if self.state == GameState::GameOver{
-                        self.reset_game();
-                    }
-                }
-                console::Key::Char('n') =>{
-                    if self.state == GameState::GameOver{
-                        break;
-                    }
-                }
-                _ => {}
+            let key = term.read_key().unwrap();
+            if key == console::Key::Char('h') && self.game.state() == GameState::HandSelection
+            && self.hold_values.is_none(){
+                println!("Calculating...");
+            }
+            if !self.handle_key(key){
+                break;
             }
         }
+        println!("Exiting... (seed {})", self.game.seed());
     }
 
     fn print_stats(&self){
-        println!("Funds: {:<10}Bet: {:<10}Round: {:<10}", 
-        self.funds, self.bet_amount, self.round,);
+        let bet = if self.game.hands() > 1{
+            format!("{}x{}", self.game.bet_amount(), self.game.hands())
+        } else {
+            self.game.bet_amount().to_string()
+        };
+        let credits = format!("{} ({})", self.game.funds(),
+        format_euros(self.game.denomination().value(self.game.funds())));
+        println!("Credits: {:<18}Bet: {:<10}Win: {:<10}Round: {:<10}Jackpot: {:<10}",
+        credits, bet, self.game.pending_win(), self.game.round(),
+        self.game.jackpot().pool());
+        println!("Credit: {}  Cashed in: {}  Cashed out: {}", self.game.denomination(),
+        format_euros(self.game.cashed_in()), format_euros(self.game.cashed_out()));
     }
 }
 
-
-fn main() {
-    //env::set_var("RUST_BACKTRACE", "1");
-    println!("Rust JokeriPokeri, a programming excercise");
-
-    let mut game = JokeriPokeri::new();
-    game.play();
+/// Prints the cards in two rows, the suits and the values.
+fn print_cards(cards: &[Option<Card>; 5]){
+    // suits row
+    for element in cards{
+        match element{
+            Some(Card::Natural{ suit, .. }) =>{
+                print!(" {:<2}", suit.symbol());
+            }
+            Some(Card::Joker) =>{
+                print!(" {:<2}", 'J');
+            }
+            None =>{
+                print!(" _ ");
+            }
+        }
+    }
+    println!();
+    // values row
+    for element in cards{
+        match element{
+            Some(Card::Natural{ rank, .. }) =>{
+                print!(" {:<2}", rank);
+            }
+            Some(Card::Joker) =>{
+                print!(" {:<2}", '*');
+            }
+            None =>{
+                print!(" _ ");
+            }
+        }
+    }
+    println!();
 }
 
-#[cfg(test)]
-#[allow(unused_variables)]
-mod tests{
-    use super::*;
-
-    #[test]
-    fn straight(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(3, uni_hearts));
-        hand.cards[2] = Some(Card::new(4, uni_clubs));
-        hand.cards[3] = Some(Card::new(5, uni_clubs));
-        hand.cards[4] = Some(Card::new(6, uni_clubs));
+/// Prints the label under the marked cards.
+fn print_marks(marks: [bool; 5], label: &str){
+    for marked in marks{
+        if marked{
+            print!("{}", label);
+        } else {
+            print!("   ");
+        }
+    }
+    println!();
+}
 
-        assert_eq!(hand.is_straight(), true);
+/// Prints the exact return of the paytable for the full deck.
+fn print_rtp(paytable: &Paytable, rules: &Rules, bet: u32){
+    println!("Calculating the return of {} with bet {} and {} jokers...", paytable.name, bet, rules.jokers);
+    let report = calculate_rtp(&Deck::with_jokers(0, rules.jokers).cards, paytable, bet);
+    println!("Starting hands: {}", report.hands);
+    println!("Return to player: {:.4} %", report.rtp * 100.0);
+    println!("Variance: {:.4} (standard deviation {:.4}) per unit bet",
+    report.variance, report.variance.sqrt());
+    println!();
+    println!("{:<25}{:>12}{:>14}{:>12}", "Prize", "Frequency", "One in", "Return %");
+    for (prize, frequency) in Prize::ALL.iter().zip(report.frequencies){
+        let multiplier = f64::from(paytable.multiplier(*prize, bet));
+        println!("{:<25}{:>12.6}{:>14.1}{:>12.4}", prize.as_str(), frequency,
+        1.0 / frequency, frequency * multiplier * 100.0);
+    }
+}
 
-        hand.cards[0] = Some(Card::new(10, uni_spade));
+/// Options of the simulate subcommand.
+struct SimulationOptions{
+    strategy: String,
+    sessions: u32,
+    rounds: u32,
+    curve_path: Option<PathBuf>,
+}
 
-        assert_eq!(hand.is_straight(), false);
+/// Runs the simulation and prints the report.
+fn print_simulation(options: &SimulationOptions, paytable: &Paytable, rules: &Rules, bet: u32, seed: u64)
+->Result<(), String>{
+    let strategy = strategy_by_name(&options.strategy)
+    .ok_or(format!("Unknown strategy {}, use nothing, winners or optimal", options.strategy))?;
+    if options.sessions == 0 || options.rounds == 0{
+        return Err("Sessions and rounds must be above zero".to_string());
     }
-    #[test]
-    fn flush(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(10, uni_spade));
-        hand.cards[1] = Some(Card::new(6, uni_spade));
-        hand.cards[2] = Some(Card::new(4, uni_spade));
-        hand.cards[3] = Some(Card::new(13, uni_spade));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
-
-        assert_eq!(hand.is_flush(), true);
-
-        hand.cards[0] = Some(Card::new(10, uni_spade));
-        hand.cards[1] = Some(Card::new(6, uni_spade));
-        hand.cards[2] = Some(Card::new(4, uni_hearts));
-        hand.cards[3] = Some(Card::new(13, uni_spade));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
-        
-        assert_eq!(hand.is_flush(), false);
+    println!("Simulating {} sessions of up to {} rounds, strategy {}, paytable {}, bet {}, seed {}",
+    options.sessions, options.rounds, strategy.name(), paytable.name, bet, seed);
+    let report = simulate(strategy.as_ref(), paytable, rules, options.sessions, options.rounds, bet, seed);
+
+    println!();
+    println!("Rounds played: {}", report.rounds_played);
+    println!("Average return: {:.4} %", report.won as f64 / report.wagered as f64 * 100.0);
+    println!("Bust probability: {:.2} %",
+    f64::from(report.busted) / f64::from(options.sessions) * 100.0);
+
+    println!();
+    println!("Average funds after round:");
+    let step = (options.rounds / 10).max(1);
+    for round in (0..=options.rounds).step_by(step as usize){
+        println!("{:>10}{:>12.1}", round, report.bankroll_curve[round as usize]);
     }
-    #[test]
-    fn straight_flush(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(3, uni_spade));
-        hand.cards[2] = Some(Card::new(4, uni_spade));
-        hand.cards[3] = Some(Card::new(5, uni_spade));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
-
-        assert_eq!(hand.is_straight_flush(), true);
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(3, uni_spade));
-        hand.cards[2] = Some(Card::new(4, uni_hearts));
-        hand.cards[3] = Some(Card::new(5, uni_spade));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
 
-        assert_eq!(hand.is_straight_flush(), false);
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(3, uni_spade));
-        hand.cards[2] = Some(Card::new(10, uni_spade));
-        hand.cards[3] = Some(Card::new(5, uni_spade));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
-
-        assert_eq!(hand.is_straight_flush(), false);
+    println!();
+    println!("Rounds survived:");
+    let mut survived = report.survived.clone();
+    survived.sort_unstable();
+    println!("{:>10}{:>12}", "median", survived[survived.len() / 2]);
+    let mut low = 0;
+    for high in [10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, u32::MAX]{
+        let high = high.min(options.rounds - 1);
+        if low > high{
+            break;
+        }
+        let count = survived.iter().filter(|&&r| r >= low && r <= high).count();
+        println!("{:>10}{:>11.2}%", format!("{}-{}", low, high),
+        count as f64 / survived.len() as f64 * 100.0);
+        low = high + 1;
     }
-    #[test]
-    fn four_of_a_kind(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(2, uni_hearts));
-        hand.cards[2] = Some(Card::new(2, uni_clubs));
-        hand.cards[3] = Some(Card::new(2, uni_diamond));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
+    println!("{:>10}{:>11.2}%", "all",
+    f64::from(options.sessions - report.busted) / f64::from(options.sessions) * 100.0);
 
-        assert_eq!(hand.is_four_of_a_kind(), true);
-
-        hand.cards[2] = Some(Card::new(5, uni_clubs));
-
-        assert_eq!(hand.is_four_of_a_kind(), false);
+    if let Some(path) = &options.curve_path{
+        let mut csv = String::from("round,average_funds\n");
+        for (round, funds) in report.bankroll_curve.iter().enumerate(){
+            csv.push_str(&format!("{},{}\n", round, funds));
+        }
+        std::fs::write(path, csv)
+        .map_err(|e| format!("Can not write bankroll curve {}: {}", path.display(), e))?;
     }
+    Ok(())
+}
 
-    #[test]
-    fn three_of_a_kind(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(2, uni_hearts));
-        hand.cards[2] = Some(Card::new(2, uni_clubs));
-        hand.cards[3] = Some(Card::new(12, uni_diamond));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
-
-        assert_eq!(hand.is_three_of_a_kind(), true);
-
-        hand.cards[2] = Some(Card::new(5, uni_clubs));
+/// Directory for the game's files, JOKERI_POKERI_HOME or ~/.rust_jokeri_pokeri
+fn data_dir()->PathBuf{
+    if let Some(dir) = std::env::var_os("JOKERI_POKERI_HOME"){
+        return PathBuf::from(dir);
+    }
+    match std::env::var_os("HOME"){
+        Some(home)=>{ PathBuf::from(home).join(".rust_jokeri_pokeri") }
+        None=>{ PathBuf::from(".") }
+    }
+}
 
-        assert_eq!(hand.is_three_of_a_kind(), false);
+/// Steps through the rounds of a hand history file.
+fn replay(path: &Path)->Result<(), String>{
+    let records = HandRecord::read_all(path)?;
+    if records.is_empty(){
+        return Err(format!("No rounds in hand history {}", path.display()));
     }
-    #[test]
-    fn full_house(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
+    let term = Term::stdout();
+    let mut index = 0;
+    loop {
+        let record = &records[index];
+        let _ = term.clear_screen();
+        println!("Round {} hand {} ({}/{})  Bet: {}", record.round, record.hand + 1,
+        index + 1, records.len(), record.bet);
+        println!();
+        println!("Dealt:");
+        print_cards(&record.initial.map(Some));
+        print_marks(record.held, "HLD");
+        println!();
+        println!("Drawn:");
+        let drawn = Hand{ cards: record.final_cards().map(Some) };
+        print_cards(&drawn.cards);
+        print_marks(drawn.winning_cards(), "WIN");
+        println!();
+        println!();
+        match &record.prize{
+            Some(prize) if record.jackpot > 0 =>{
+                println!("{}! Payout {} and jackpot {}", prize.as_str(), record.payout, record.jackpot);
+            }
+            Some(prize)=>{ println!("{}! Payout {}", prize.as_str(), record.payout); }
+            None=>{ println!("No win."); }
+        }
+        println!();
+        println!("left/right - previous/next round");
+        println!("escape - quit");
 
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(2, uni_hearts));
-        hand.cards[2] = Some(Card::new(2, uni_clubs));
-        hand.cards[3] = Some(Card::new(4, uni_diamond));
-        hand.cards[4] = Some(Card::new(4, uni_spade));
+        match term.read_key().map_err(|e| e.to_string())?{
+            console::Key::ArrowLeft if index > 0 =>{
+                index -= 1;
+            }
+            console::Key::ArrowRight | console::Key::Enter | console::Key::Char(' ')
+            if index + 1 < records.len() =>{
+                index += 1;
+            }
+            console::Key::Escape =>{
+                return Ok(());
+            }
+            _ =>{}
+        }
+    }
+}
 
-        assert_eq!(hand.is_full_house(), true);
+enum Command{
+    Play,
+    Replay,
+    Rtp,
+    Simulate,
+}
 
-        hand.cards[4] = Some(Card::new(5, uni_clubs));
+/// Command line options, on top of the settings file.
+struct Options{
+    command: Command,
+    paytable: Paytable,
+    rules: Rules,
+    seed: u64,
+    save_path: PathBuf,
+    history_path: PathBuf,
+    jackpot_path: PathBuf,
+    stats_path: PathBuf,
+    leaderboard_path: PathBuf,
+    /// Percentage of every bet going to the jackpot
+    jackpot_percent: u32,
+    /// Hands played at once in a new game
+    hands: usize,
+    /// Value of a credit in a new game
+    denomination: Denomination,
+    /// Money put in with the cash in key, in cents
+    cash_in_cents: u64,
+    bet: u32,
+    /// Line based terminal instead of the full-screen one
+    plain: bool,
+    simulation: SimulationOptions,
+}
+impl Options{
+    fn parse(args: &[String])->Result<Self, String>{
+        // the settings file first, the rest of the arguments override it
+        let config = match args.iter().position(|arg| arg == "--config"){
+            Some(i) =>{
+                let path = args.get(i + 1).ok_or("--config needs a file")?;
+                Config::load(Path::new(path), true)?
+            }
+            None =>{
+                Config::load(&data_dir().join("config.toml"), false)?
+            }
+        };
+        let mut paytable_path = config.paytable;
+        let mut denomination = config.ui.denomination;
+        let mut cash_in = config.ui.cash_in;
+        let mut bet = None;
+        let mut options = Options{
+            command: Command::Play,
+            paytable: Paytable::classic(),
+            rules: config.rules,
+            seed: rand::random(),
+            save_path: data_dir().join("save.json"),
+            history_path: data_dir().join("history.jsonl"),
+            jackpot_path: data_dir().join("jackpot.json"),
+            stats_path: data_dir().join("stats.json"),
+            leaderboard_path: data_dir().join("leaderboard.json"),
+            jackpot_percent: DEFAULT_JACKPOT_PERCENT,
+            hands: config.ui.hands,
+            denomination: Denomination::default(),
+            cash_in_cents: 0,
+            bet: 0,
+            plain: config.ui.plain,
+            simulation: SimulationOptions{
+                strategy: "winners".to_string(),
+                sessions: 10000,
+                rounds: 1000,
+                curve_path: None,
+            },
+        };
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next(){
+            match arg.as_str(){
+                "--config" =>{
+                    // already read
+                    args.next();
+                }
+                "--paytable" =>{
+                    let path = args.next().ok_or("--paytable needs a file")?;
+                    paytable_path = Some(PathBuf::from(path));
+                }
+                "--funds" =>{
+                    let funds = args.next().ok_or("--funds needs a number")?;
+                    options.rules.starting_funds = funds.parse()
+                    .map_err(|_| format!("Invalid starting funds {}", funds))?;
+                }
+                "--bets" =>{
+                    let bets = args.next().ok_or("--bets needs a list like 20,40,60")?;
+                    options.rules.bets = bets.split(',')
+                    .map(|bet| bet.trim().parse().map_err(|_| format!("Invalid bet {} in {}", bet, bets)))
+                    .collect::<Result<_, _>>()?;
+                }
+                "--jokers" =>{
+                    let jokers = args.next().ok_or("--jokers needs a number")?;
+                    options.rules.jokers = jokers.parse()
+                    .map_err(|_| format!("Invalid number of jokers {}", jokers))?;
+                }
+                "--seed" =>{
+                    let seed = args.next().ok_or("--seed needs a number")?;
+                    options.seed = seed.parse()
+                    .map_err(|_| format!("Invalid seed {}", seed))?;
+                }
+                "--save" =>{
+                    let path = args.next().ok_or("--save needs a file")?;
+                    options.save_path = PathBuf::from(path);
+                }
+                "--history" =>{
+                    let path = args.next().ok_or("--history needs a file")?;
+                    options.history_path = PathBuf::from(path);
+                }
+                "--jackpot" =>{
+                    let percent = args.next().ok_or("--jackpot needs a percentage")?;
+                    options.jackpot_percent = percent.parse().ok().filter(|&p| p <= 100)
+                    .ok_or(format!("Invalid jackpot percentage {}", percent))?;
+                }
+                "--hands" =>{
+                    let hands = args.next().ok_or("--hands needs a number")?;
+                    options.hands = hands.parse()
+                    .map_err(|_| format!("Invalid number of hands {}", hands))?;
+                }
+                "--denomination" =>{
+                    denomination = args.next().ok_or("--denomination needs an amount of euros")?.clone();
+                }
+                "--cash-in" =>{
+                    cash_in = args.next().ok_or("--cash-in needs an amount of euros")?.clone();
+                }
+                "--bet" =>{
+                    let value = args.next().ok_or("--bet needs a number")?;
+                    bet = Some(value.parse()
+                    .map_err(|_| format!("Invalid bet {}", value))?);
+                }
+                "--plain" =>{
+                    options.plain = true;
+                }
+                "--strategy" =>{
+                    let strategy = args.next().ok_or("--strategy needs a name")?;
+                    options.simulation.strategy = strategy.clone();
+                }
+                "--sessions" =>{
+                    let sessions = args.next().ok_or("--sessions needs a number")?;
+                    options.simulation.sessions = sessions.parse()
+                    .map_err(|_| format!("Invalid number of sessions {}", sessions))?;
+                }
+                "--rounds" =>{
+                    let rounds = args.next().ok_or("--rounds needs a number")?;
+                    options.simulation.rounds = rounds.parse()
+                    .map_err(|_| format!("Invalid number of rounds {}", rounds))?;
+                }
+                "--curve" =>{
+                    let path = args.next().ok_or("--curve needs a file")?;
+                    options.simulation.curve_path = Some(PathBuf::from(path));
+                }
+                "simulate" =>{
+                    options.command = Command::Simulate;
+                }
+                "rtp" =>{
+                    options.command = Command::Rtp;
+                }
+                "replay" =>{
+                    options.command = Command::Replay;
+                    // optional history file
+                    if let Some(path) = args.next_if(|arg| !arg.starts_with("--")){
+                        options.history_path = PathBuf::from(path);
+                    }
+                }
+                _ =>{
+                    return Err(format!("Unknown argument {}", arg));
+                }
+            }
+        }
 
-        assert_eq!(hand.is_full_house(), false);
+        // check the settings together, wherever they came from
+        options.rules.validate().map_err(|e| format!("Invalid rules: {}", e))?;
+        if let Some(path) = &paytable_path{
+            options.paytable = Paytable::load(path)?;
+        }
+        options.rules.validate_paytable(&options.paytable)?;
+        if options.hands == 0 || options.hands > MAX_HANDS{
+            return Err(format!("Invalid number of hands {}, use 1-{}", options.hands, MAX_HANDS));
+        }
+        let wager = u64::from(options.rules.min_bet()) * options.hands as u64;
+        if wager > u64::from(options.rules.starting_funds){
+            return Err(format!("Starting funds {} do not cover {} hands of the smallest bet {}",
+            options.rules.starting_funds, options.hands, options.rules.min_bet()));
+        }
+        options.denomination = denomination.parse()?;
+        options.cash_in_cents = parse_euros(&cash_in)?;
+        if options.denomination.credits(options.cash_in_cents) == 0{
+            return Err(format!("Cash in of {} does not buy a credit of {}",
+            format_euros(options.cash_in_cents), options.denomination));
+        }
+        options.bet = bet.unwrap_or(options.rules.min_bet());
+        // the return and the simulation divide by the bet
+        options.rules.check_bet(options.bet)?;
+        Ok(options)
     }
-    #[test]
-    fn two_pairs(){
-        let mut hand = Hand::new();
-        let uni_spade = char::from_u32(0x2660).unwrap();
-        let uni_hearts = char::from_u32(0x2661).unwrap();
-        let uni_diamond = char::from_u32(0x2662).unwrap();
-        let uni_clubs = char::from_u32(0x2663).unwrap();
-
-        hand.cards[0] = Some(Card::new(2, uni_spade));
-        hand.cards[1] = Some(Card::new(2, uni_hearts));
-        hand.cards[2] = Some(Card::new(4, uni_clubs));
-        hand.cards[3] = Some(Card::new(4, uni_diamond));
-        hand.cards[4] = Some(Card::new(6, uni_spade));
+}
 
-        assert_eq!(hand.is_two_pairs(), true);
+fn main() {
+    //env::set_var("RUST_BACKTRACE", "1");
+    println!("Rust JokeriPokeri, a programming excercise");
 
-        hand.cards[0] = Some(Card::new(7, uni_clubs));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match Options::parse(&args){
+        Ok(options)=>{ options }
+        Err(e)=>{
+            eprintln!("{}", e);
+            eprintln!("Usage: rust_jokeri_pokeri [replay [file] | rtp | simulate] \
+            [--paytable <file.toml|file.json>] [--seed <u64>] [--save <file>] \
+            [--config <file.toml>] [--funds <n>] [--bets <n,n,...>] [--jokers <n>] \
+            [--history <file>] [--jackpot <percent>] [--hands <n>] [--denomination 0.20|0.50|1] \
+            [--cash-in <euros>] [--bet <n>] [--plain] [--strategy nothing|winners|optimal] \
+            [--sessions <n>] [--rounds <n>] [--curve <file.csv>]");
+            std::process::exit(2);
+        }
+    };
 
-        assert_eq!(hand.is_two_pairs(), false);
-    }
-    #[test]
-    fn deal(){
-        let mut game = JokeriPokeri::new();
-        // hand must be empty before deal
-        for option_card in &game.hand.cards{
-            match option_card{
-                Some(..) => {
-                    panic!("Cards in hand before deal");
-                }
-                None => {}
+    match options.command{
+        Command::Replay =>{
+            if let Err(e) = replay(&options.history_path){
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::Rtp =>{
+            print_rtp(&options.paytable, &options.rules, options.bet);
+            return;
+        }
+        Command::Simulate =>{
+            if let Err(e) = print_simulation(&options.simulation, &options.paytable,
+            &options.rules, options.bet, options.seed){
+                eprintln!("{}", e);
+                std::process::exit(1);
             }
+            return;
         }
-        // has_nones
-        //assert_eq!(game.hand.has_nones(), true);
+        Command::Play =>{}
+    }
 
-        let deck_len_before_deal = game.deck.cards.len();
-        // deal
-        game.deal();
-        //assert_eq!(game.hand.has_nones(), false);
+    let mut game = None;
+    if options.save_path.exists(){
+        println!("Resume saved game y/n?");
+        if Term::stdout().read_key().unwrap() == console::Key::Char('y'){
+            match JokeriPokeri::load(&options.save_path){
+                Ok(saved)=>{ game = Some(saved); }
+                Err(e)=>{ eprintln!("{}", e); }
+            }
+        }
+    }
+    let mut game = game.unwrap_or_else(||{
+        let mut game = JokeriPokeri::with_rules(options.paytable, options.rules, options.seed);
+        // checked when parsing the options, and allowed in a new game
+        let _ = game.set_hands(options.hands);
+        let _ = game.set_denomination(options.denomination);
+        game
+    });
+    game.set_jackpot(Jackpot::load(&options.jackpot_path, options.jackpot_percent)
+    .unwrap_or_else(|e|{
+        eprintln!("{}", e);
+        Jackpot::new(options.jackpot_percent)
+    }));
+    let mut ui = TerminalUi::new(game, Some(options.history_path), options.cash_in_cents);
+    ui.lifetime = Stats::load(&options.stats_path).unwrap_or_else(|e|{
+        eprintln!("{}", e);
+        Stats::default()
+    });
+    ui.leaderboard = Leaderboard::load(&options.leaderboard_path).unwrap_or_else(|e|{
+        eprintln!("{}", e);
+        Leaderboard::default()
+    });
+    ui.leaderboard_path = Some(options.leaderboard_path);
+    if options.plain{
+        ui.play();
+    }
+    else if let Err(e) = tui::run(&mut ui){
+        eprintln!("{}", e);
+    }
+    if let Err(e) = ui.lifetime.save(&options.stats_path){
+        eprintln!("{}", e);
+    }
+    let game = ui.game;
 
-        let deck_len_after_deal = game.deck.cards.len();
-        assert_eq!(deck_len_before_deal - deck_len_after_deal, game.hand.cards.len());
-        assert_eq!(game.discarded.len(), 0);
-        
-        game.selected[2] = true;
-        game.selected[3] = true;
+    if let Err(e) = game.jackpot().save(&options.jackpot_path){
+        eprintln!("{}", e);
+    }
 
-        game.deal();
-        // discarding from hand
-        assert_eq!(game.discarded.len(), game.hand.cards.len() - 2);
-        let deck_len_after_hand_selection_deal = game.deck.cards.len();
-        assert_eq!(deck_len_after_deal - deck_len_after_hand_selection_deal, game.hand.cards.len()-2);
+    if game.state() == GameState::GameOver{
+        // nothing left to resume
+        let _ = std::fs::remove_file(&options.save_path);
+    }
+    else if let Err(e) = game.save(&options.save_path){
+        eprintln!("{}", e);
     }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
     #[test]
-    fn reset_deck_and_hand(){
-        let mut game = JokeriPokeri::new();
-        let deck_size_before_deal = game.deck.cards.len();
-        game.deal();
-        game.selected[1] = true;
-        game.selected[2] = true;
-        game.deal();
-        game.reset_deck_and_hand();
-        assert_eq!(deck_size_before_deal, game.deck.cards.len());
-        assert_eq!(game.discarded.len(), 0);
-    }
-}
\ No newline at end of file
+    fn parse_bet(){
+        let path = std::env::temp_dir().join(format!("jokeri_config_{}.toml", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let parse = |args: &[&str]|{
+            let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            args.extend(["--config".to_string(), path.display().to_string()]);
+            Options::parse(&args)
+        };
+        assert_eq!(parse(&["rtp"]).unwrap().bet, 20);
+        assert_eq!(parse(&["rtp", "--bet", "60"]).unwrap().bet, 60);
+        // the return of a bet of 0 is not a number
+        assert!(parse(&["rtp", "--bet", "0"]).is_err());
+        assert!(parse(&["rtp", "--bet", "50"]).is_err());
+        assert!(parse(&["simulate", "--bet", "120"]).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,102 @@
+//! Money value of the credits.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Formats cents as euros and cents, like "12.40 €".
+pub fn format_euros(cents: u64)->String{
+    format!("{}.{:02} \u{20ac}", cents / 100, cents % 100)
+}
+
+/// Parses euros with at most two decimals, like "0.5" or "10.00", to cents.
+pub fn parse_euros(s: &str)->Result<u64, String>{
+    let invalid = || format!("Invalid amount of euros {}", s);
+    let s = s.trim().trim_end_matches('\u{20ac}').trim();
+    let (euros, cents) = s.split_once(['.', ',']).unwrap_or((s, ""));
+    if (euros.is_empty() && cents.is_empty()) || cents.len() > 2
+    || !euros.bytes().chain(cents.bytes()).all(|b| b.is_ascii_digit()){
+        return Err(invalid());
+    }
+    let euros: u64 = if euros.is_empty(){ 0 } else { euros.parse().map_err(|_| invalid())? };
+    // "0.5" is fifty cents
+    let cents: u64 = format!("{:0<2}", cents).parse().map_err(|_| invalid())?;
+    euros.checked_mul(100).and_then(|euros| euros.checked_add(cents)).ok_or_else(invalid)
+}
+
+/// Value of one credit on the machine.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Denomination{
+    /// 0.20 €, the original machine
+    #[default]
+    Cents20,
+    /// 0.50 €
+    Cents50,
+    /// 1.00 €
+    Euro1,
+}
+impl Denomination{
+    /// Every denomination, from the smallest.
+    pub const ALL: [Denomination; 3] = [Denomination::Cents20, Denomination::Cents50, Denomination::Euro1];
+
+    /// Value of a credit in cents.
+    pub fn cents(&self)->u64{
+        match self{
+            Denomination::Cents20 => 20,
+            Denomination::Cents50 => 50,
+            Denomination::Euro1 => 100,
+        }
+    }
+
+    /// Money value of the credits in cents.
+    pub fn value(&self, credits: u32)->u64{
+        u64::from(credits) * self.cents()
+    }
+
+    /// Whole credits bought with the cents, the rest is not accepted.
+    pub fn credits(&self, cents: u64)->u32{
+        u32::try_from(cents / self.cents()).unwrap_or(u32::MAX)
+    }
+}
+impl fmt::Display for Denomination{
+    fn fmt(&self, f: &mut fmt::Formatter)->fmt::Result{
+        f.pad(&format_euros(self.cents()))
+    }
+}
+impl FromStr for Denomination{
+    type Err = String;
+
+    /// Parses the value of a credit in euros, like "0.20" or "1".
+    fn from_str(s: &str)->Result<Self, Self::Err>{
+        let cents = parse_euros(s)?;
+        Denomination::ALL.into_iter()
+        .find(|denomination| denomination.cents() == cents)
+        .ok_or(format!("Invalid denomination {}, use 0.20, 0.50 or 1", s))
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn euros(){
+        assert_eq!(format_euros(1240), "12.40 \u{20ac}");
+        assert_eq!(format_euros(5), "0.05 \u{20ac}");
+        assert_eq!(parse_euros("12.40"), Ok(1240));
+        assert_eq!(parse_euros("0,5"), Ok(50));
+        assert_eq!(parse_euros("10 \u{20ac}"), Ok(1000));
+        assert_eq!(parse_euros(".20"), Ok(20));
+        assert!(parse_euros("1.234").is_err());
+        assert!(parse_euros("-1").is_err());
+        assert!(parse_euros("").is_err());
+
+        assert_eq!("0.2".parse(), Ok(Denomination::Cents20));
+        assert_eq!("1".parse(), Ok(Denomination::Euro1));
+        assert!("0.30".parse::<Denomination>().is_err());
+        assert_eq!(Denomination::Cents50.value(7), 350);
+        assert_eq!(Denomination::Cents50.credits(1020), 20);
+        assert_eq!(Denomination::Cents20.to_string(), "0.20 \u{20ac}");
+    }
+}
@@ -0,0 +1,171 @@
+//! Payout multipliers of the prizes.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::game::GameError;
+use crate::hand::Prize;
+
+/// One row of a paytable.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PaytableRow{
+    /// The prize of the row
+    pub prize: Prize,
+    /// How many times the bet the prize pays.
+    pub multiplier: u32,
+    /// Smallest bet the prize is paid on.
+    #[serde(default)]
+    pub min_bet: u32,
+}
+
+/// Payout multipliers of a machine revision.
+/// Prizes missing from the table pay nothing.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Paytable{
+    /// Name of the machine revision, shown in reports
+    pub name: String,
+    /// Rows of the prizes that pay, at most one each
+    #[serde(rename = "prize")]
+    pub rows: Vec<PaytableRow>,
+}
+impl Paytable{
+    /// The paytable of the current machine, used when no other is selected.
+    pub fn classic()->Self{
+        toml::from_str(include_str!("../paytables/classic.toml"))
+        .expect("built-in paytable is valid")
+    }
+
+    /// Reads a paytable from a TOML or JSON (by file extension) file.
+    pub fn load(path: &Path)->Result<Self, String>{
+        let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Can not read paytable {}: {}", path.display(), e))?;
+        let paytable: Paytable = if path.extension().is_some_and(|ext| ext == "json"){
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&text).map_err(|e| e.to_string())
+        }
+        .map_err(|e| format!("Invalid paytable {}: {}", path.display(), e))?;
+        paytable.check_duplicates()
+        .map_err(|e| format!("Invalid paytable {}: {}", path.display(), e))?;
+        Ok(paytable)
+    }
+
+    /// Checks that every prize is listed at most once, and that every
+    /// payout with the biggest allowed bet fits in the credit counters.
+    pub fn validate(&self, max_bet: u32)->Result<(), String>{
+        self.check_duplicates()?;
+        match self.rows.iter().find(|row| row.multiplier.checked_mul(max_bet).is_none()){
+            Some(row) => Err(format!("{} pays {} times the bet, too much to count with the bet {}",
+            row.prize.as_str(), row.multiplier, max_bet)),
+            None => Ok(()),
+        }
+    }
+
+    fn check_duplicates(&self)->Result<(), String>{
+        for (i, row) in self.rows.iter().enumerate(){
+            if self.rows[..i].iter().any(|other| other.prize == row.prize){
+                return Err(format!("{} is listed more than once", row.prize.as_str()));
+            }
+        }
+        Ok(())
+    }
+
+    /// The row of the prize, if it pays anything.
+    pub fn row(&self, prize: Prize)->Option<&PaytableRow>{
+        self.rows.iter().find(|row| row.prize == prize)
+    }
+
+    /// The best prize with a row of its own, the one the jackpot is paid on.
+    pub fn top_prize(&self)->Option<Prize>{
+        Prize::ALL.into_iter().find(|&prize| self.row(prize).is_some())
+    }
+
+    /// The prize whose row pays the prize with the given bet, None if it
+    /// pays nothing. A prize without a row is paid as its fallback prize,
+    /// if it has one.
+    pub fn paid_prize(&self, prize: Prize, bet: u32)->Option<Prize>{
+        match (self.row(prize), prize.fallback()){
+            (Some(row), _) if bet >= row.min_bet=>{
+                Some(prize)
+            }
+            (None, Some(fallback))=>{
+                self.paid_prize(fallback, bet)
+            }
+            _=>{
+                None
+            }
+        }
+    }
+
+    /// How many times the given bet the prize pays.
+    pub fn multiplier(&self, prize: Prize, bet: u32)->u32{
+        self.paid_prize(prize, bet).and_then(|prize| self.row(prize)).map_or(0, |row| row.multiplier)
+    }
+
+    /// How much the prize pays with the given bet.
+    pub fn payout(&self, prize: Prize, bet: u32)->Result<u32, GameError>{
+        self.multiplier(prize, bet).checked_mul(bet).ok_or(GameError::Overflow)
+    }
+
+    /// How much the prize pays with the given bet, counted wide enough
+    /// that it never overflows.
+    pub fn payout_wide(&self, prize: Prize, bet: u32)->u64{
+        u64::from(self.multiplier(prize, bet)) * u64::from(bet)
+    }
+
+    /// The biggest payout of any prize with the given bet.
+    pub fn max_payout(&self, bet: u32)->Result<u32, GameError>{
+        Prize::ALL.into_iter().map(|prize| self.payout(prize, bet))
+        .try_fold(0, |max, payout| payout.map(|payout| payout.max(max)))
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn paytable(){
+        let paytable = Paytable::classic();
+        assert_eq!(paytable.payout(Prize::FiveOfAKind, 20), Ok(1000));
+        assert_eq!(paytable.payout(Prize::TwoPairs, 20), Ok(40));
+
+        let older = Paytable::load(Path::new("paytables/older.json")).unwrap();
+        assert_eq!(older.payout(Prize::StraightFlush, 20), Ok(800));
+        // two pairs pay only on the bigger bets
+        assert_eq!(older.payout(Prize::TwoPairs, 40), Ok(0));
+        assert_eq!(older.payout(Prize::TwoPairs, 60), Ok(120));
+
+        let mut missing = Paytable::classic();
+        missing.rows.retain(|row| row.prize != Prize::Flush);
+        assert_eq!(missing.payout(Prize::Flush, 20), Ok(0));
+
+        // royal flushes are paid as straight flushes without rows of their own
+        assert_eq!(paytable.payout(Prize::RoyalFlush, 20), Ok(2000));
+        assert_eq!(paytable.payout(Prize::WildRoyalFlush, 20), Ok(900));
+        assert_eq!(older.payout(Prize::RoyalFlush, 20), Ok(800));
+        assert_eq!(older.payout(Prize::WildRoyalFlush, 20), Ok(800));
+        let mut natural_only = Paytable::classic();
+        natural_only.rows.retain(|row| row.prize != Prize::WildRoyalFlush);
+        assert_eq!(natural_only.payout(Prize::RoyalFlush, 20), Ok(2000));
+        assert_eq!(natural_only.payout(Prize::WildRoyalFlush, 20), Ok(800));
+        assert_eq!(natural_only.paid_prize(Prize::WildRoyalFlush, 20), Some(Prize::StraightFlush));
+        assert_eq!(natural_only.paid_prize(Prize::RoyalFlush, 20), Some(Prize::RoyalFlush));
+        assert_eq!(older.paid_prize(Prize::TwoPairs, 40), None);
+
+        let mut duplicate = Paytable::classic();
+        duplicate.rows.push(duplicate.rows[0].clone());
+        assert!(duplicate.validate(100).is_err());
+
+        // a payout must fit in the counters with the biggest bet
+        let mut big = Paytable::classic();
+        assert_eq!(big.validate(100), Ok(()));
+        big.rows.last_mut().unwrap().multiplier = 50_000_000;
+        assert!(big.validate(100).is_err());
+        assert_eq!(big.payout(big.rows.last().unwrap().prize, 100), Err(GameError::Overflow));
+        assert_eq!(big.max_payout(100), Err(GameError::Overflow));
+        assert_eq!(big.payout_wide(big.rows.last().unwrap().prize, 100), 5_000_000_000);
+        assert_eq!(paytable.max_payout(20), Ok(2000));
+    }
+}
@@ -0,0 +1,60 @@
+//! Reading and writing the JSON files kept between sessions.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// Writes the value to a JSON file, what names it in the errors.
+pub(crate) fn save_json<T: Serialize>(value: &T, path: &Path, what: &str)->Result<(), String>{
+    if let Some(dir) = path.parent(){
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    // write to a temporary file first, so a failed save
+    // never destroys the previous one
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, text)
+    .and_then(|_| std::fs::rename(&temp_path, path))
+    .map_err(|e| format!("Can not save {} to {}: {}", what, path.display(), e))
+}
+
+/// Reads a value saved with save_json, the default if there is no file.
+pub(crate) fn load_json_or_default<T: DeserializeOwned + Default>(path: &Path, what: &str)
+->Result<T, String>{
+    if !path.exists(){
+        return Ok(T::default());
+    }
+    let text = std::fs::read_to_string(path)
+    .map_err(|e| format!("Can not read {} {}: {}", what, path.display(), e))?;
+    serde_json::from_str(&text)
+    .map_err(|e| format!("Invalid {} {}: {}", what, path.display(), e))
+}
+
+/// A file in the temporary directory for a test, removed if left over
+/// from an earlier run.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str)->std::path::PathBuf{
+    let path = std::env::temp_dir().join(format!("jokeri_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn save_and_load_json(){
+        let path = temp_path("persist.json");
+        assert_eq!(load_json_or_default::<Vec<u32>>(&path, "numbers"), Ok(Vec::new()));
+        save_json(&vec![1, 2, 3], &path, "numbers").unwrap();
+        assert_eq!(load_json_or_default::<Vec<u32>>(&path, "numbers"), Ok(vec![1, 2, 3]));
+        assert!(!path.with_extension("tmp").exists());
+
+        std::fs::write(&path, "[1,").unwrap();
+        let error = load_json_or_default::<Vec<u32>>(&path, "numbers").unwrap_err();
+        assert!(error.starts_with("Invalid numbers"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+//! Exact return to player of a paytable.
+
+use crate::card::Card;
+use crate::hand::{Hand, Prize};
+use crate::paytable::Paytable;
+
+/// Exact return of a paytable over every starting hand, drawing with the
+/// hold that pays the most on average.
+pub struct RtpReport{
+    /// Number of starting hands
+    pub hands: u64,
+    /// Average payout per unit bet
+    pub rtp: f64,
+    /// Variance of the payout per unit bet
+    pub variance: f64,
+    /// Probability of each prize after the draw, in the order of Prize::ALL
+    pub frequencies: [f64; Prize::ALL.len()],
+}
+
+/// Binomial coefficients C(n, k) for k up to 5.
+fn binomials(n: usize)->Vec<[u64; 6]>{
+    let mut table = vec![[0u64; 6]; n + 1];
+    for i in 0..=n{
+        table[i][0] = 1;
+        for k in 1..6{
+            if i > 0{
+                table[i][k] = table[i - 1][k - 1] + table[i - 1][k];
+            }
+        }
+    }
+    table
+}
+
+/// Index of every subset of five card indices, each subset's index being
+/// its rank among the subsets of the same size. Bit i of the mask is
+/// hand[i], hand must be sorted.
+fn subset_ranks(hand: &[usize; 5], binomials: &[[u64; 6]])->[usize; 32]{
+    let mut ranks = [0usize; 32];
+    for mask in 1..32usize{
+        let highest = 31 - (mask as u32).leading_zeros() as usize;
+        let size = mask.count_ones() as usize;
+        ranks[mask] = ranks[mask & !(1 << highest)]
+        + binomials[hand[highest]][size] as usize;
+    }
+    ranks
+}
+
+/// Calls f with every sorted five card index subset of 0..n,
+/// whose smallest index is first.
+fn for_each_hand_from(n: usize, first: usize, mut f: impl FnMut(&[usize; 5])){
+    for b in first + 1..n{
+        for c in b + 1..n{
+            for d in c + 1..n{
+                for e in d + 1..n{
+                    f(&[first, b, c, d, e]);
+                }
+            }
+        }
+    }
+}
+
+/// Calculates the exact return of the paytable with the given bet, over
+/// every starting hand dealt from the cards.
+///
+/// For every subset of up to five cards the counts of prizes of all the
+/// hands containing it are tabled first. The prizes of a hold are then
+/// the prizes of the hands containing the held cards, minus those that
+/// contain any of the discarded cards (inclusion-exclusion).
+pub fn calculate_rtp(cards: &[Card], paytable: &Paytable, bet: u32)->RtpReport{
+    const PRIZES: usize = Prize::ALL.len();
+    let n = cards.len();
+    assert!(n >= 10, "need at least ten cards to draw five replacements");
+    let binomials = binomials(n);
+    let payouts = Prize::ALL.map(|prize| paytable.payout_wide(prize, bet));
+
+    // prize of every final hand, PRIZES for no prize
+    let mut final_prizes = vec![PRIZES as u8; binomials[n][5] as usize];
+    // prize counts and payout sums of the hands containing each subset
+    let mut prize_counts: Vec<Vec<[u32; PRIZES]>> = (0..5)
+    .map(|k| vec![[0; PRIZES]; binomials[n][k] as usize]).collect();
+    let mut payout_sums: Vec<Vec<u64>> = (0..=5)
+    .map(|k| vec![0; binomials[n][k] as usize]).collect();
+    let mut hand = Hand::new();
+    for first in 0..n{
+        for_each_hand_from(n, first, |indices|{
+            for (slot, &i) in hand.cards.iter_mut().zip(indices){
+                *slot = Some(cards[i]);
+            }
+            let prize = match hand.evaluate(){
+                Some(prize)=>{ prize as usize }
+                None=>{ return; }
+            };
+            let ranks = subset_ranks(indices, &binomials);
+            final_prizes[ranks[31]] = prize as u8;
+            for (mask, &rank) in ranks.iter().enumerate(){
+                let size = mask.count_ones() as usize;
+                payout_sums[size][rank] += payouts[prize];
+                if size < 5{
+                    prize_counts[size][rank][prize] += 1;
+                }
+            }
+        });
+    }
+
+    // every starting hand, split over threads by the first card
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let totals: Vec<(f64, f64, [f64; PRIZES])> = std::thread::scope(|scope|{
+        let handles: Vec<_> = (0..threads).map(|thread|{
+            let (binomials, final_prizes) = (&binomials, &final_prizes);
+            let (prize_counts, payout_sums) = (&prize_counts, &payout_sums);
+            let payouts = &payouts;
+            scope.spawn(move ||{
+                let mut sum = 0.0;
+                let mut sum_of_squares = 0.0;
+                let mut hits = [0.0; PRIZES];
+                for first in (thread..n).step_by(threads){
+                    for_each_hand_from(n, first, |indices|{
+                        let ranks = subset_ranks(indices, binomials);
+                        // payouts of the draws of every hold, the hands
+                        // containing the discarded cards removed
+                        let mut sums = [0i64; 32];
+                        for (mask, sum) in sums.iter_mut().enumerate(){
+                            *sum = payout_sums[mask.count_ones() as usize][ranks[mask]] as i64;
+                        }
+                        for bit in 0..5{
+                            for mask in 0..32{
+                                if mask & (1 << bit) == 0{
+                                    sums[mask] -= sums[mask | (1 << bit)];
+                                }
+                            }
+                        }
+                        let draws = |mask: usize| binomials[n - 5][5 - mask.count_ones() as usize] as f64;
+                        let best = (0..32).max_by(|&a, &b|{
+                            (sums[a] as f64 / draws(a)).total_cmp(&(sums[b] as f64 / draws(b)))
+                        }).unwrap();
+
+                        // prizes of the best hold
+                        let mut best_hits = [0i64; PRIZES];
+                        for mask in (0..32usize).filter(|mask| mask & best == best){
+                            let sign = if (mask.count_ones() - best.count_ones()) % 2 == 0{ 1 } else { -1 };
+                            if mask == 31{
+                                let prize = final_prizes[ranks[31]] as usize;
+                                if prize < PRIZES{
+                                    best_hits[prize] += sign;
+                                }
+                            }
+                            else{
+                                let counts = &prize_counts[mask.count_ones() as usize][ranks[mask]];
+                                for (hit, &count) in best_hits.iter_mut().zip(counts){
+                                    *hit += sign * i64::from(count);
+                                }
+                            }
+                        }
+                        let draws = draws(best);
+                        for (prize, &hit) in best_hits.iter().enumerate(){
+                            let probability = hit as f64 / draws;
+                            let payout = payouts[prize] as f64;
+                            hits[prize] += probability;
+                            sum += probability * payout;
+                            sum_of_squares += probability * payout * payout;
+                        }
+                    });
+                }
+                (sum, sum_of_squares, hits)
+            })
+        }).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let hands = binomials[n][5];
+    let mut sum = 0.0;
+    let mut sum_of_squares = 0.0;
+    let mut frequencies = [0.0; PRIZES];
+    for (thread_sum, thread_squares, thread_hits) in totals{
+        sum += thread_sum;
+        sum_of_squares += thread_squares;
+        for (frequency, hit) in frequencies.iter_mut().zip(thread_hits){
+            *frequency += hit;
+        }
+    }
+    let bet = f64::from(bet);
+    let mean = sum / hands as f64 / bet;
+    let mean_of_squares = sum_of_squares / hands as f64 / (bet * bet);
+    RtpReport{
+        hands,
+        rtp: mean,
+        variance: mean_of_squares - mean * mean,
+        frequencies: frequencies.map(|frequency| frequency / hands as f64),
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::hand::for_each_combination;
+
+    #[test]
+    fn rtp(){
+        let cards = vec![
+            Card::new(Rank::Two, Suit::Spades),
+            Card::new(Rank::Two, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Spades),
+            Card::new(Rank::Three, Suit::Hearts),
+            Card::new(Rank::Four, Suit::Spades),
+            Card::new(Rank::Five, Suit::Spades),
+            Card::new(Rank::Six, Suit::Hearts),
+            Card::new(Rank::Nine, Suit::Spades),
+            Card::Joker,
+            Card::Joker,
+            Card::new(Rank::King, Suit::Hearts),
+        ];
+        let paytable = Paytable::classic();
+        let report = calculate_rtp(&cards, &paytable, 20);
+
+        // the best hold of every starting hand, one by one
+        let mut total = 0.0;
+        let mut hands = 0;
+        for_each_combination(&cards, 5, |_| hands += 1);
+        let indices: Vec<usize> = (0..cards.len()).collect();
+        let mut starting = Vec::new();
+        for first in 0..cards.len(){
+            for_each_hand_from(cards.len(), first, |hand| starting.push(*hand));
+        }
+        assert_eq!(starting.len(), hands);
+        for hand_indices in starting{
+            let mut hand = Hand::new();
+            for (slot, &i) in hand.cards.iter_mut().zip(&hand_indices){
+                *slot = Some(cards[i]);
+            }
+            let remaining: Vec<Card> = indices.iter()
+            .filter(|i| !hand_indices.contains(i)).map(|&i| cards[i]).collect();
+            let values = hand.hold_values(&remaining, &paytable, 20);
+            total += values.iter().copied().fold(0.0, f64::max);
+        }
+        assert_eq!(report.hands, hands as u64);
+        assert!((report.rtp - total / hands as f64 / 20.0).abs() < 1e-9);
+        assert!(report.variance > 0.0);
+        let returned: f64 = Prize::ALL.iter().zip(report.frequencies)
+        .map(|(prize, frequency)| frequency * f64::from(paytable.multiplier(*prize, 20))).sum();
+        assert!((report.rtp - returned).abs() < 1e-9);
+    }
+}
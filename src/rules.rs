@@ -0,0 +1,122 @@
+//! Adjustable rules of the machine.
+
+use serde::{Deserialize, Serialize};
+
+use crate::deck::DEFAULT_JOKERS;
+use crate::paytable::Paytable;
+
+/// Most jokers a deck can have.
+pub const MAX_JOKERS: usize = 4;
+
+/// Starting funds, bets and the deck of a game. The defaults are the
+/// rules of the original machine.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules{
+    /// Credits a new game starts with
+    pub starting_funds: u32,
+    /// Allowed bets per hand, from the smallest. The jackpot is paid on
+    /// the last one.
+    pub bets: Vec<u32>,
+    /// Jokers in the deck
+    pub jokers: usize,
+}
+impl Default for Rules{
+    fn default()->Self{
+        Self{
+            starting_funds: 100,
+            bets: vec![20, 40, 60, 80, 100],
+            jokers: DEFAULT_JOKERS,
+        }
+    }
+}
+impl Rules{
+    /// The smallest allowed bet, 0 if there are none.
+    pub fn min_bet(&self)->u32{
+        self.bets.first().copied().unwrap_or(0)
+    }
+
+    /// The biggest allowed bet, 0 if there are none.
+    pub fn max_bet(&self)->u32{
+        self.bets.last().copied().unwrap_or(0)
+    }
+
+    /// Checks that the bet is one of the allowed bets.
+    pub fn check_bet(&self, bet: u32)->Result<(), String>{
+        if !self.bets.contains(&bet){
+            return Err(format!("Bet {} is not one of the allowed bets {:?}", bet, self.bets));
+        }
+        Ok(())
+    }
+
+    /// Checks that the rules make a playable game.
+    pub fn validate(&self)->Result<(), String>{
+        if self.bets.is_empty(){
+            return Err("The list of allowed bets is empty".to_string());
+        }
+        if self.bets.contains(&0){
+            return Err("A bet of 0 is not allowed".to_string());
+        }
+        if let Some(pair) = self.bets.windows(2).find(|pair| pair[0] >= pair[1]){
+            return Err(format!("The allowed bets must grow from the smallest, {} is followed by {}",
+            pair[0], pair[1]));
+        }
+        if self.starting_funds < self.min_bet(){
+            return Err(format!("Starting funds {} do not cover the smallest bet {}",
+            self.starting_funds, self.min_bet()));
+        }
+        if self.jokers > MAX_JOKERS{
+            return Err(format!("{} jokers is too many, use 0-{}", self.jokers, MAX_JOKERS));
+        }
+        Ok(())
+    }
+
+    /// Checks the paytable with the biggest allowed bet, and that every
+    /// prize of it can be won with some allowed bet.
+    pub fn validate_paytable(&self, paytable: &Paytable)->Result<(), String>{
+        paytable.validate(self.max_bet())
+        .map_err(|e| format!("Invalid paytable {}: {}", paytable.name, e))?;
+        match paytable.rows.iter().find(|row| row.min_bet > self.max_bet()){
+            Some(row) => Err(format!("{} of paytable {} needs a bet of {}, above the biggest bet {}",
+            row.prize.as_str(), paytable.name, row.min_bet, self.max_bet())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn validate_rules(){
+        let rules = Rules::default();
+        assert_eq!(rules.validate(), Ok(()));
+        assert_eq!((rules.min_bet(), rules.max_bet()), (20, 100));
+        assert_eq!(rules.check_bet(60), Ok(()));
+        assert!(rules.check_bet(0).is_err());
+        assert!(rules.check_bet(50).is_err());
+        assert_eq!(rules.validate_paytable(&Paytable::classic()), Ok(()));
+
+        let invalid = [
+            Rules{ bets: Vec::new(), ..Rules::default() },
+            Rules{ bets: vec![0, 10], ..Rules::default() },
+            Rules{ bets: vec![10, 30, 20], ..Rules::default() },
+            Rules{ bets: vec![10, 10], ..Rules::default() },
+            Rules{ starting_funds: 10, ..Rules::default() },
+            Rules{ jokers: MAX_JOKERS + 1, ..Rules::default() },
+        ];
+        for rules in invalid{
+            assert!(rules.validate().is_err(), "{:?}", rules);
+        }
+
+        let mut paytable = Paytable::classic();
+        paytable.rows[0].min_bet = 200;
+        assert!(rules.validate_paytable(&paytable).is_err());
+        let mut paytable = Paytable::classic();
+        paytable.rows[0].multiplier = u32::MAX / 50;
+        assert_eq!(Rules{ bets: vec![20, 50], ..Rules::default() }.validate_paytable(&paytable), Ok(()));
+        assert!(rules.validate_paytable(&paytable).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Headless play with strategies deciding the holds.
+
+use crate::card::Card;
+use crate::game::{GameError, JokeriPokeri};
+use crate::hand::{mask_to_selection, Hand};
+use crate::paytable::Paytable;
+use crate::rules::Rules;
+
+/// Decides which cards to hold when playing without a player.
+pub trait Strategy{
+    /// Name used to pick the strategy on the command line.
+    fn name(&self)->&'static str;
+    /// Returns the slots to hold in the dealt hand, given the cards
+    /// left in the deck.
+    fn hold(&self, hand: &Hand, remaining: &[Card], paytable: &Paytable, bet: u32)->[bool; 5];
+}
+
+/// Draws five new cards every time.
+pub struct HoldNothing;
+impl Strategy for HoldNothing{
+    fn name(&self)->&'static str{
+        "nothing"
+    }
+
+    fn hold(&self, _hand: &Hand, _remaining: &[Card], _paytable: &Paytable, _bet: u32)->[bool; 5]{
+        [false; 5]
+    }
+}
+
+/// Holds the cards of a winning hand, otherwise jokers and pairs.
+pub struct HoldWinners;
+impl Strategy for HoldWinners{
+    fn name(&self)->&'static str{
+        "winners"
+    }
+
+    fn hold(&self, hand: &Hand, _remaining: &[Card], _paytable: &Paytable, _bet: u32)->[bool; 5]{
+        if hand.evaluate().is_some(){
+            return hand.winning_cards();
+        }
+        let counts = match hand.value_counts(){
+            Some(counts)=>{ counts.values }
+            None=>{ return [false; 5]; }
+        };
+        hand.cards.map(|element| element.is_some_and(|card|{
+            card.is_joker() || card.rank().is_some_and(|rank| counts[rank.value() as usize] >= 2)
+        }))
+    }
+}
+
+/// Holds the cards with the best expected payout. Exact, but slow.
+pub struct HoldOptimal;
+impl Strategy for HoldOptimal{
+    fn name(&self)->&'static str{
+        "optimal"
+    }
+
+    fn hold(&self, hand: &Hand, remaining: &[Card], paytable: &Paytable, bet: u32)->[bool; 5]{
+        let values = hand.hold_values(remaining, paytable, bet);
+        let best = (0..values.len())
+        .max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap();
+        mask_to_selection(best)
+    }
+}
+
+/// Finds one of the built-in strategies by its name.
+pub fn strategy_by_name(name: &str)->Option<Box<dyn Strategy>>{
+    let strategies: [Box<dyn Strategy>; 3] = [
+        Box::new(HoldNothing),
+        Box::new(HoldWinners),
+        Box::new(HoldOptimal),
+    ];
+    strategies.into_iter().find(|strategy| strategy.name() == name)
+}
+
+/// Results of playing many sessions with a strategy.
+pub struct SimulationReport{
+    /// Rounds played in every session together
+    pub rounds_played: u64,
+    /// Credits bet in every session together
+    pub wagered: u64,
+    /// Credits won in every session together, before doubling
+    pub won: u64,
+    /// Sessions that ran out of funds before the round limit
+    pub busted: u32,
+    /// Rounds each session played before running out of funds
+    /// or reaching the round limit
+    pub survived: Vec<u32>,
+    /// Average funds after each round over all sessions, busted ones
+    /// staying at what they had left
+    pub bankroll_curve: Vec<f64>,
+}
+
+/// Plays one round with the strategy, returning the win.
+fn play_round(game: &mut JokeriPokeri, strategy: &dyn Strategy, bet: u32)->Result<u32, GameError>{
+    game.start_round(bet)?;
+    let hold = strategy.hold(game.hand(), &game.deck().cards, game.paytable(), bet);
+    for (slot, held) in hold.into_iter().enumerate(){
+        if held{
+            game.toggle_hold(slot)?;
+        }
+    }
+    game.draw()?;
+    let won = game.pending_win();
+    game.end_round()?;
+    Ok(won)
+}
+
+/// Plays sessions of up to max_rounds rounds from the starting funds of
+/// the rules, always betting the same and never doubling up. Session i
+/// is dealt with seed + i.
+pub fn simulate(strategy: &dyn Strategy, paytable: &Paytable, rules: &Rules, sessions: u32,
+max_rounds: u32, bet: u32, seed: u64)->SimulationReport{
+    let mut report = SimulationReport{
+        rounds_played: 0,
+        wagered: 0,
+        won: 0,
+        busted: 0,
+        survived: Vec::with_capacity(sessions as usize),
+        bankroll_curve: vec![0.0; max_rounds as usize + 1],
+    };
+    for session in 0..sessions{
+        let mut game = JokeriPokeri::with_rules(paytable.clone(), rules.clone(),
+        seed.wrapping_add(u64::from(session)));
+        report.bankroll_curve[0] += f64::from(game.funds());
+        let mut rounds = 0;
+        while rounds < max_rounds{
+            // out of funds for the bet
+            let Ok(won) = play_round(&mut game, strategy, bet) else { break; };
+            report.wagered += u64::from(bet);
+            report.won += u64::from(won);
+            rounds += 1;
+            report.bankroll_curve[rounds as usize] += f64::from(game.funds());
+        }
+        report.rounds_played += u64::from(rounds);
+        if rounds < max_rounds{
+            report.busted += 1;
+            for funds in &mut report.bankroll_curve[rounds as usize + 1..]{
+                *funds += f64::from(game.funds());
+            }
+        }
+        report.survived.push(rounds);
+    }
+    for funds in &mut report.bankroll_curve{
+        *funds /= f64::from(sessions);
+    }
+    report
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+    use crate::card::{Rank, Suit};
+
+    #[test]
+    fn strategies(){
+        let mut hand = Hand::new();
+        let paytable = Paytable::classic();
+
+        hand.cards[0] = Some(Card::new(Rank::Two, Suit::Spades));
+        hand.cards[1] = Some(Card::new(Rank::Two, Suit::Hearts));
+        hand.cards[2] = Some(Card::new(Rank::Five, Suit::Clubs));
+        hand.cards[3] = Some(Card::Joker);
+        hand.cards[4] = Some(Card::new(Rank::King, Suit::Clubs));
+
+        assert_eq!(HoldNothing.hold(&hand, &[], &paytable, 20), [false; 5]);
+        assert_eq!(HoldWinners.hold(&hand, &[], &paytable, 20), [true, true, false, true, false]);
+
+        // no win, hold the pair
+        hand.cards[3] = Some(Card::new(Rank::Nine, Suit::Clubs));
+        assert_eq!(HoldWinners.hold(&hand, &[], &paytable, 20), [true, true, false, false, false]);
+
+        assert_eq!(strategy_by_name("optimal").unwrap().name(), "optimal");
+        assert!(strategy_by_name("random").is_none());
+    }
+    #[test]
+    fn simulate_sessions(){
+        let paytable = Paytable::classic();
+        let report = simulate(&HoldWinners, &paytable, &Rules::default(), 20, 50, 20, 1);
+        assert_eq!(report.survived.len(), 20);
+        assert_eq!(report.wagered, report.rounds_played * 20);
+        assert_eq!(report.bankroll_curve.len(), 51);
+        assert_eq!(report.bankroll_curve[0], 100.0);
+        assert_eq!(report.survived.iter().map(|&r| u64::from(r)).sum::<u64>(),
+        report.rounds_played);
+
+        // same seed, same results
+        let again = simulate(&HoldWinners, &paytable, &Rules::default(), 20, 50, 20, 1);
+        assert_eq!(again.won, report.won);
+        assert_eq!(again.survived, report.survived);
+    }
+}
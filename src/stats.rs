@@ -0,0 +1,121 @@
+//! Statistics of the played hands, for a session or a lifetime.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::hand::Prize;
+use crate::history::HandRecord;
+use crate::persist::{load_json_or_default, save_json};
+
+/// Counters of the played hands. Wins are what the paytable and the
+/// jackpot paid, before any doubling.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats{
+    /// Hands drawn
+    pub hands: u64,
+    /// Hands won with each prize
+    pub hits: BTreeMap<Prize, u64>,
+    /// Credits bet
+    pub wagered: u64,
+    /// Credits won, the jackpot included
+    pub won: u64,
+    /// Biggest payout of a single hand, the jackpot included
+    pub biggest_win: u32,
+    /// Hands lost in a row since the latest win
+    pub losing_streak: u32,
+    /// Most hands lost in a row
+    pub longest_losing_streak: u32,
+}
+impl Stats{
+    /// Counts a drawn hand.
+    pub fn record(&mut self, record: &HandRecord){
+        let win = record.payout.saturating_add(record.jackpot);
+        self.hands += 1;
+        self.wagered += u64::from(record.bet);
+        self.won += u64::from(win);
+        self.biggest_win = self.biggest_win.max(win);
+        if let Some(prize) = record.prize{
+            *self.hits.entry(prize).or_insert(0) += 1;
+        }
+        if win > 0{
+            self.losing_streak = 0;
+        }
+        else{
+            self.losing_streak += 1;
+            self.longest_losing_streak = self.longest_losing_streak.max(self.losing_streak);
+        }
+    }
+
+    /// Hands won with the prize.
+    pub fn hits(&self, prize: Prize)->u64{
+        self.hits.get(&prize).copied().unwrap_or(0)
+    }
+
+    /// Won per wagered, None before the first hand.
+    pub fn return_rate(&self)->Option<f64>{
+        if self.wagered == 0{
+            return None;
+        }
+        Some(self.won as f64 / self.wagered as f64)
+    }
+
+    /// Reads the statistics saved with save, empty if there is no file.
+    pub fn load(path: &Path)->Result<Self, String>{
+        load_json_or_default(path, "statistics")
+    }
+
+    /// Writes the statistics to a file.
+    pub fn save(&self, path: &Path)->Result<(), String>{
+        save_json(self, path, "statistics")
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_variables)]
+mod tests{
+    use super::*;
+    use crate::card::Card;
+
+    fn record(bet: u32, prize: Option<Prize>, payout: u32)->HandRecord{
+        HandRecord{
+            round: 1,
+            hand: 0,
+            bet,
+            initial: [Card::Joker; 5],
+            held: [true; 5],
+            replacements: Vec::new(),
+            prize,
+            payout,
+            jackpot: 0,
+        }
+    }
+
+    #[test]
+    fn record_hands(){
+        let path = crate::persist::temp_path("stats.json");
+        let mut stats = Stats::load(&path).unwrap();
+        assert_eq!(stats.return_rate(), None);
+
+        stats.record(&record(20, None, 0));
+        stats.record(&record(20, None, 0));
+        stats.record(&record(40, Some(Prize::TwoPairs), 80));
+        stats.record(&record(20, None, 0));
+        let mut jackpot = record(100, Some(Prize::RoyalFlush), 10000);
+        jackpot.jackpot = 50;
+        stats.record(&jackpot);
+
+        assert_eq!(stats.hands, 5);
+        assert_eq!(stats.wagered, 200);
+        assert_eq!(stats.won, 10130);
+        assert_eq!(stats.biggest_win, 10050);
+        assert_eq!(stats.hits(Prize::TwoPairs), 1);
+        assert_eq!(stats.hits(Prize::Flush), 0);
+        assert_eq!((stats.losing_streak, stats.longest_losing_streak), (0, 2));
+
+        stats.save(&path).unwrap();
+        assert_eq!(Stats::load(&path).unwrap(), stats);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,382 @@
+//! Full-screen front-end drawn with ratatui, sharing the key handling
+//! of the line based terminal front-end.
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use rust_jokeri_pokeri::hand::{mask_to_selection, selection_to_mask};
+use rust_jokeri_pokeri::{format_euros, Card, GameState, Prize, Suit};
+use std::io;
+
+use crate::{Overlay, TerminalUi, SUMMARY_ROWS};
+
+/// Width and height of a drawn card face.
+const CARD_WIDTH: u16 = 7;
+const CARD_HEIGHT: u16 = 5;
+
+/// Plays the game on the alternate screen until the player quits.
+pub fn run(ui: &mut TerminalUi)->io::Result<()>{
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, ui);
+    ratatui::restore();
+    println!("Exiting... (seed {})", ui.game.seed());
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, ui: &mut TerminalUi)->io::Result<()>{
+    loop {
+        // only the changed cells are written, so there is no flicker
+        terminal.draw(|frame| render(ui, frame))?;
+        let key = match event::read()?{
+            Event::Key(key) if key.kind == KeyEventKind::Press =>{ key.code }
+            _ =>{ continue; }
+        };
+        let key = match key{
+            KeyCode::Enter => console::Key::Enter,
+            KeyCode::Esc => console::Key::Escape,
+            KeyCode::Backspace => console::Key::Backspace,
+            KeyCode::Left => console::Key::ArrowLeft,
+            KeyCode::Right => console::Key::ArrowRight,
+            KeyCode::Char(c) => console::Key::Char(c),
+            _ => continue,
+        };
+        if key == console::Key::Char('h') && ui.game.state() == GameState::HandSelection
+        && ui.hold_values.is_none(){
+            ui.message = Some("Calculating...".to_string());
+            terminal.draw(|frame| render(ui, frame))?;
+        }
+        if !ui.handle_key(key){
+            return Ok(());
+        }
+    }
+}
+
+fn render(ui: &TerminalUi, frame: &mut Frame){
+    match ui.overlay{
+        Some(Overlay::Stats) =>{
+            render_statistics(ui, frame, frame.area());
+            return;
+        }
+        Some(Overlay::Leaderboard) =>{
+            render_leaderboard(ui, frame, frame.area());
+            return;
+        }
+        _ =>{}
+    }
+    let game = &ui.game;
+    let paytable_height = Prize::ALL.iter()
+    .filter(|&&prize| game.paytable().row(prize).is_some()).count() as u16 + 2;
+    let extra_height = if game.extra_hands().is_empty(){ 0 } else { game.extra_hands().len() as u16 + 2 };
+    let [paytable_area, hand_area, extra_area, stats_area, message_area] = Layout::vertical([
+        Constraint::Length(paytable_height),
+        Constraint::Length(CARD_HEIGHT + 5),
+        Constraint::Length(extra_height),
+        Constraint::Length(4),
+        Constraint::Min(6),
+    ]).areas(frame.area());
+
+    render_paytable(ui, frame, paytable_area);
+    render_hand(ui, frame, hand_area);
+    if !game.extra_hands().is_empty(){
+        render_extra_hands(ui, frame, extra_area);
+    }
+    render_stats(ui, frame, stats_area);
+    render_messages(ui, frame, message_area);
+}
+
+fn render_statistics(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let header = Style::new().add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::styled(format!("{:<25}{:>12}{:>12}", "", "Session", "Lifetime"), header)];
+    for (i, (name, session, lifetime)) in ui.stats_rows().into_iter().enumerate(){
+        if i == SUMMARY_ROWS{
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::raw(format!("{:<25}{:>12}{:>12}", name, session, lifetime)));
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::styled("any key - back", Style::new().fg(Color::DarkGray)));
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Statistics")), area);
+}
+
+fn render_leaderboard(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let mut lines: Vec<Line> = ui.leaderboard_lines().into_iter().enumerate()
+    .map(|(i, line)|{
+        if i == 0{
+            Line::styled(line, Style::new().add_modifier(Modifier::BOLD))
+        } else {
+            Line::raw(line)
+        }
+    }).collect();
+    lines.push(Line::raw(""));
+    lines.push(Line::styled("any key - back", Style::new().fg(Color::DarkGray)));
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Leaderboard")), area);
+}
+
+/// Prizes of the paytable rows that paid the hands of the round.
+fn won_prizes(ui: &TerminalUi)->Vec<Prize>{
+    let game = &ui.game;
+    if game.state() != GameState::PayOut && game.state() != GameState::DoubleUp{
+        return Vec::new();
+    }
+    std::iter::once(game.hand()).chain(game.extra_hands())
+    .filter_map(|hand| hand.evaluate())
+    .filter_map(|prize| game.paytable().paid_prize(prize, game.bet_amount())).collect()
+}
+
+fn render_paytable(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let game = &ui.game;
+    let won = won_prizes(ui);
+    let lines: Vec<Line> = Prize::ALL.into_iter()
+    .filter(|&prize| game.paytable().row(prize).is_some())
+    .map(|prize|{
+        let text = format!("{:<25}{:>8}", prize.as_str(), game.paytable().payout_wide(prize, game.bet_amount()));
+        if won.contains(&prize){
+            Line::styled(text, Style::new().fg(Color::Black).bg(Color::Yellow)
+            .add_modifier(Modifier::BOLD))
+        } else {
+            Line::raw(text)
+        }
+    }).collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(game.paytable().name.as_str())), area);
+}
+
+/// Style of a card face, red or black on white.
+fn face_style(card: &Card)->Style{
+    let color = match card.suit(){
+        Some(Suit::Hearts | Suit::Diamonds) => Color::Red,
+        Some(Suit::Spades | Suit::Clubs) => Color::Black,
+        None => Color::Magenta,
+    };
+    Style::new().fg(color).bg(Color::White).add_modifier(Modifier::BOLD)
+}
+
+/// Draws a bordered card face, or the back of a card when the slot is empty.
+fn render_card(frame: &mut Frame, area: Rect, card: Option<&Card>, border: Style){
+    let block = Block::bordered().border_type(BorderType::Rounded).border_style(border);
+    let face = match card{
+        Some(card @ Card::Natural{ rank, suit }) =>{
+            let rank = rank.to_string();
+            Paragraph::new(vec![
+                Line::raw(format!("{:<5}", rank)),
+                Line::raw(format!("{:^5}", suit.symbol())),
+                Line::raw(format!("{:>5}", rank)),
+            ]).style(face_style(card))
+        }
+        Some(card @ Card::Joker) =>{
+            Paragraph::new(vec![
+                Line::raw(format!("{:<5}", "JK")),
+                Line::raw("JOKER"),
+                Line::raw(format!("{:>5}", "JK")),
+            ]).style(face_style(card))
+        }
+        None =>{
+            Paragraph::new(vec![Line::raw("\u{2592}".repeat(5)); 3])
+            .style(Style::new().fg(Color::Blue))
+        }
+    };
+    frame.render_widget(face.block(block), area);
+}
+
+fn render_hand(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let game = &ui.game;
+    let block = Block::bordered().title("Hand");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let after_draw = game.state() == GameState::PayOut || game.state() == GameState::DoubleUp;
+    let winning = if after_draw{ game.hand().winning_cards() } else { [false; 5] };
+    let best = ui.hold_values.as_ref().filter(|_| game.state() == GameState::HandSelection)
+    .map(|values|{
+        let best = (0..values.len())
+        .max_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap();
+        mask_to_selection(best)
+    });
+
+    let columns = Layout::horizontal([Constraint::Length(CARD_WIDTH); 5]).spacing(2).split(inner);
+    for (i, column) in columns.iter().enumerate(){
+        let [card_area, held_area, selector_area, best_area] = Layout::vertical([
+            Constraint::Length(CARD_HEIGHT),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ]).areas(*column);
+
+        let held = game.state() == GameState::HandSelection && game.selected()[i];
+        let selected = game.state() == GameState::HandSelection && ui.selector == i;
+        let border = if selected{
+            Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else if held || winning[i]{
+            Style::new().fg(Color::Yellow)
+        } else {
+            Style::new().fg(Color::DarkGray)
+        };
+        render_card(frame, card_area, game.hand().cards[i].as_ref(), border);
+
+        let label = if held{
+            Span::styled("HELD", Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        } else if winning[i]{
+            Span::styled("WIN", Style::new().fg(Color::Green).add_modifier(Modifier::BOLD))
+        } else {
+            Span::raw("")
+        };
+        frame.render_widget(Paragraph::new(label).alignment(Alignment::Center), held_area);
+        if selected{
+            frame.render_widget(Paragraph::new("^").alignment(Alignment::Center)
+            .style(Style::new().fg(Color::Cyan)), selector_area);
+        }
+        if best.is_some_and(|best| best[i]){
+            frame.render_widget(Paragraph::new("BEST").alignment(Alignment::Center)
+            .style(Style::new().fg(Color::Cyan)), best_area);
+        }
+    }
+}
+
+/// A card as a short colored label, like the faces but on one line.
+fn card_span(card: Option<&Card>)->Span<'static>{
+    match card{
+        Some(card @ Card::Natural{ rank, suit }) =>{
+            Span::styled(format!("{:>3}{} ", rank, suit.symbol()), face_style(card))
+        }
+        Some(card @ Card::Joker) =>{
+            Span::styled(" JK  ", face_style(card))
+        }
+        None =>{
+            Span::raw("  _  ")
+        }
+    }
+}
+
+fn render_extra_hands(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let game = &ui.game;
+    let after_draw = game.state() == GameState::PayOut || game.state() == GameState::DoubleUp;
+    let lines: Vec<Line> = game.extra_hands().iter().enumerate().map(|(i, hand)|{
+        let mut spans = vec![Span::raw(format!("{:>3} ", i + 2))];
+        for (slot, card) in hand.cards.iter().enumerate(){
+            // before the draw the hands show the held cards only
+            let card = if after_draw{ card.as_ref() }
+            else { game.hand().cards[slot].as_ref().filter(|_| game.selected()[slot]) };
+            spans.push(card_span(card));
+            spans.push(Span::raw(" "));
+        }
+        if let Some(prize) = hand.evaluate().filter(|_| after_draw){
+            spans.push(Span::styled(format!(" {} {}", prize.as_str(),
+            game.paytable().payout_wide(prize, game.bet_amount())), Style::new().fg(Color::Yellow)));
+        }
+        Line::from(spans)
+    }).collect();
+    frame.render_widget(Paragraph::new(lines)
+    .block(Block::bordered().title(format!("{} hands", game.hands()))), area);
+}
+
+fn render_stats(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let game = &ui.game;
+    let bet = if game.hands() > 1{
+        format!("{}x{}", game.bet_amount(), game.hands())
+    } else {
+        game.bet_amount().to_string()
+    };
+    let label = Style::new().fg(Color::DarkGray);
+    let value = Style::new().add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    for (name, amount) in [
+        ("Credits", game.funds().to_string()),
+        ("Money", format_euros(game.denomination().value(game.funds()))),
+        ("Bet", bet),
+        ("Win", game.pending_win().to_string()),
+        ("Round", game.round().to_string()),
+        ("Jackpot", game.jackpot().pool().to_string()),
+    ]{
+        spans.push(Span::styled(format!("{} ", name), label));
+        spans.push(Span::styled(format!("{:<10}", amount), value));
+    }
+    let meters = Line::styled(format!("Credit {}  Cashed in {}  Cashed out {}", game.denomination(),
+    format_euros(game.cashed_in()), format_euros(game.cashed_out())), label);
+    frame.render_widget(Paragraph::new(vec![Line::from(spans), meters]).block(Block::bordered()), area);
+}
+
+fn render_messages(ui: &TerminalUi, frame: &mut Frame, area: Rect){
+    let game = &ui.game;
+    let mut lines = Vec::new();
+    if let Some(message) = &ui.message{
+        lines.push(Line::styled(message.clone(), Style::new().fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD)));
+    }
+    if let Some(card) = game.double_up_card(){
+        if game.state() == GameState::PayOut || game.state() == GameState::DoubleUp{
+            lines.push(Line::from(vec![Span::raw("Double up card: "), card_span(Some(&card))]));
+        }
+    }
+    let keys: &[&str] = match game.state(){
+        GameState::Betting =>{
+            &["b - cycle bet amount", "m - cycle number of hands", "c - cash in", "o - cash out",
+            "t - statistics", "l - leaderboard", "enter - start game"]
+        }
+        GameState::HandSelection =>{
+            if let Some(values) = &ui.hold_values{
+                let best = values.iter().copied().fold(f64::MIN, f64::max);
+                let current = values[selection_to_mask(&game.selected())];
+                lines.push(Line::raw(format!(
+                    "Best hold pays {:.2}, your hold pays {:.2} on average", best, current)));
+            }
+            &["left/right - move selector", "space - select card", "h - hint best hold",
+            "enter - draw"]
+        }
+        GameState::DoubleUp =>{
+            &["s - guess small (A-6)", "l - guess large (8-K)", "enter - back"]
+        }
+        GameState::PayOut =>{
+            match game.latest_prize(){
+                _ if game.hands() > 1 && game.latest_payout() > 0 =>{
+                    lines.push(Line::raw(format!("The hands won {}.", game.latest_payout())));
+                }
+                Some(prize) =>{
+                    lines.push(Line::raw(format!("{}! You won {}.", prize.as_str(), game.latest_payout())));
+                }
+                None =>{
+                    lines.push(Line::raw("No win."));
+                }
+            }
+            if game.pending_win() > 0 && game.can_double_up(){
+                &["d - double up", "h - collect half", "enter - collect and new round"]
+            } else if game.pending_win() > 0{
+                &["h - collect half", "enter - collect and new round"]
+            } else if game.funds() != 0{
+                &["enter - new round"]
+            } else {
+                &["enter - continue"]
+            }
+        }
+        GameState::GameOver =>{
+            lines.push(Line::styled("GAME OVER", Style::new().fg(Color::Red)
+            .add_modifier(Modifier::BOLD)));
+            lines.push(Line::raw(format!("You made it round {}", game.round())));
+            lines.push(Line::raw(format!("Seed: {}", game.seed())));
+            lines.push(Line::raw(""));
+            lines.push(Line::styled("This session:", Style::new().add_modifier(Modifier::BOLD)));
+            for (name, session, _) in ui.stats_rows().into_iter().take(SUMMARY_ROWS){
+                lines.push(Line::raw(format!("{:<25}{:>12}", name, session)));
+            }
+            if let Some(Overlay::NameEntry(name)) = &ui.overlay{
+                lines.push(Line::raw(""));
+                lines.push(Line::from(vec![
+                    Span::raw("Your initials for the leaderboard: "),
+                    Span::styled(format!("{}_", name), Style::new().fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)),
+                ]));
+                &["enter - save", "escape - skip"]
+            } else {
+                &["c - cash in and play on", "y - new game", "n - quit"]
+            }
+        }
+    };
+    lines.push(Line::raw(""));
+    // escape skips the name entry instead of quitting
+    let quit: &[&str] = if matches!(ui.overlay, Some(Overlay::NameEntry(_))){ &[] } else { &["escape - quit"] };
+    for key in keys.iter().chain(quit){
+        lines.push(Line::styled(*key, Style::new().fg(Color::DarkGray)));
+    }
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered()), area);
+}